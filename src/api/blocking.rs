@@ -0,0 +1,251 @@
+use crate::api::{ApiCore, BaseUrls, DriftReport, Transport};
+use crate::error::Result;
+#[cfg(feature = "ddnet")]
+use crate::scheme::ddnet::prelude as ddnet;
+#[cfg(feature = "ddstats")]
+use crate::scheme::ddstats;
+#[cfg(feature = "ddstats")]
+use crate::scheme::Category;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+/// Synchronous client for both APIs.
+///
+/// Shares the schema types, URL builders, errors and [`Transport`]s with
+/// [`DDApi`](crate::api::DDApi), driving them on a private single-threaded
+/// runtime; there is no response cache. Like `reqwest::blocking`, it must
+/// not be created or used from within an async runtime.
+///
+/// # Examples
+/// ```rust,no_run
+/// use ddapi_rs::prelude::blocking::{BlockingClient, DDnetApi};
+///
+/// fn main() -> ddapi_rs::prelude::Result<()> {
+///     let api = BlockingClient::new();
+///     let player = api.player("nameless tee")?;
+///     println!("{}: {}", player.player, player.points.points.unwrap_or(0));
+///     Ok(())
+/// }
+/// ```
+///
+/// Any [`Transport`] works, including the fixture and mock server ones:
+/// ```rust
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::blocking::{BlockingClient, DDnetApi};
+///
+/// struct Fake;
+///
+/// impl Transport for Fake {
+///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async {
+///             Ok(HttpResponse::ok(
+///                 r#"[{"timestamp": 1715025600, "map": "Tutorial", "name": "nameless tee", "time": 30.0, "server": "GER"}]"#,
+///             ))
+///         })
+///     }
+/// }
+///
+/// let api = BlockingClient::new_with_transport(Fake);
+/// assert_eq!(api.latest_finish().unwrap()[0].name, "nameless tee");
+/// ```
+#[derive(Clone)]
+pub struct BlockingClient {
+    core: ApiCore,
+    runtime: Arc<Runtime>,
+}
+
+impl Default for BlockingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockingClient {
+    /// A client with the crate's user agent and default headers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime cannot be created, like `reqwest::blocking::Client::new`.
+    pub fn new() -> Self {
+        Self::from_core(ApiCore::new())
+    }
+
+    /// Uses a pre-configured `reqwest::Client`.
+    pub fn new_with_client(client: Client) -> Self {
+        Self::from_core(ApiCore::new_with_client(client))
+    }
+
+    /// Sends every request through `transport`, see [`Transport`].
+    pub fn new_with_transport(transport: impl Transport + 'static) -> Self {
+        Self::from_core(ApiCore::new_with_transport(transport))
+    }
+
+    fn from_core(core: ApiCore) -> Self {
+        #[cfg(feature = "cache")]
+        let core = ApiCore {
+            cache: None,
+            ..core
+        };
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the blocking client's runtime");
+        Self {
+            core,
+            runtime: Arc::new(runtime),
+        }
+    }
+
+    pub fn set_base_urls(&mut self, base_urls: BaseUrls) {
+        self.core.set_base_urls(base_urls);
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.core.set_timeout(timeout);
+    }
+
+    /// Checks every parsed response for schema drift, see [`DriftReport`].
+    pub fn set_drift_report(&mut self, report: impl Into<Option<DriftReport>>) {
+        self.core.set_drift_report(report.into());
+    }
+
+    /// Executes an API request and deserializes the JSON response.
+    pub fn _generator<T>(&self, url: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.runtime.block_on(self.core._generator_no_cache(url))
+    }
+}
+
+#[cfg(feature = "ddnet")]
+pub trait DDnetApi {
+    fn master(&self) -> Result<ddnet::Master>;
+    fn skins(&self) -> Result<ddnet::DDSkins>;
+    fn custom_master(&self, master: ddnet::MasterServer) -> Result<ddnet::Master>;
+    fn player(&self, player: &str) -> Result<ddnet::Player>;
+    fn query(&self, player: &str) -> Result<Vec<ddnet::Query>>;
+    fn query_map(&self, map: &str) -> Result<Vec<ddnet::QueryMap>>;
+    fn query_mapper(&self, player: &str) -> Result<Vec<ddnet::QueryMapper>>;
+    fn map(&self, map: &str) -> Result<ddnet::Map>;
+    fn releases_map(&self) -> Result<Vec<ddnet::ReleasesMaps>>;
+    fn status(&self) -> Result<ddnet::Status>;
+    fn latest_finish(&self) -> Result<Vec<ddnet::LatestFinishes>>;
+    fn latest_finish_with_latest(&self, latest: usize) -> Result<Vec<ddnet::LatestFinishes>>;
+}
+
+#[cfg(feature = "ddnet")]
+impl DDnetApi for BlockingClient {
+    fn master(&self) -> Result<ddnet::Master> {
+        self.custom_master(ddnet::MasterServer::One)
+    }
+
+    fn skins(&self) -> Result<ddnet::DDSkins> {
+        self._generator(&ddnet::DDSkins::api())
+    }
+
+    fn custom_master(&self, master: ddnet::MasterServer) -> Result<ddnet::Master> {
+        self._generator(&ddnet::Master::api(master))
+    }
+
+    fn player(&self, player: &str) -> Result<ddnet::Player> {
+        self._generator(&ddnet::Player::api(player))
+    }
+
+    fn query(&self, player: &str) -> Result<Vec<ddnet::Query>> {
+        self._generator(&ddnet::Query::api(player))
+    }
+
+    fn query_map(&self, map: &str) -> Result<Vec<ddnet::QueryMap>> {
+        self._generator(&ddnet::QueryMap::api(map))
+    }
+
+    fn query_mapper(&self, player: &str) -> Result<Vec<ddnet::QueryMapper>> {
+        self._generator(&ddnet::QueryMapper::api(player))
+    }
+
+    fn map(&self, map: &str) -> Result<ddnet::Map> {
+        self._generator(&ddnet::Map::api(map))
+    }
+
+    fn releases_map(&self) -> Result<Vec<ddnet::ReleasesMaps>> {
+        self._generator(&ddnet::ReleasesMaps::api())
+    }
+
+    fn status(&self) -> Result<ddnet::Status> {
+        self._generator(&ddnet::Status::api())
+    }
+
+    fn latest_finish(&self) -> Result<Vec<ddnet::LatestFinishes>> {
+        self.latest_finish_with_latest(0)
+    }
+
+    fn latest_finish_with_latest(&self, latest: usize) -> Result<Vec<ddnet::LatestFinishes>> {
+        self._generator(&ddnet::LatestFinishes::api(latest))
+    }
+}
+
+#[cfg(feature = "ddstats")]
+pub trait DDstats {
+    fn player(&self, player: &str) -> Result<ddstats::Player>;
+    fn map(&self, map: &str) -> Result<ddstats::Map>;
+    fn maps(&self) -> Result<Vec<ddstats::StatsMap>>;
+    fn profile(&self, player: &str) -> Result<ddstats::Profile>;
+    fn search_players(&self, query: &str) -> Result<Vec<ddstats::SearchPlayer>>;
+    fn search_maps(&self, query: &str) -> Result<Vec<ddstats::StatsMap>>;
+    fn leaderboard(
+        &self,
+        kind: ddstats::LeaderboardKind,
+        category: Option<Category>,
+        page: u32,
+    ) -> Result<ddstats::Leaderboard>;
+    fn servers(&self) -> Result<Vec<ddstats::StatsServer>>;
+    fn community_servers(&self, community: &str) -> Result<Vec<ddstats::StatsServer>>;
+}
+
+#[cfg(feature = "ddstats")]
+impl DDstats for BlockingClient {
+    fn player(&self, player: &str) -> Result<ddstats::Player> {
+        self._generator(&ddstats::Player::api(player))
+    }
+
+    fn map(&self, map: &str) -> Result<ddstats::Map> {
+        self._generator(&ddstats::Map::api(map))
+    }
+
+    fn maps(&self) -> Result<Vec<ddstats::StatsMap>> {
+        self._generator(&ddstats::StatsMap::api())
+    }
+
+    fn profile(&self, player: &str) -> Result<ddstats::Profile> {
+        self._generator(&ddstats::Profile::api(player))
+    }
+
+    fn search_players(&self, query: &str) -> Result<Vec<ddstats::SearchPlayer>> {
+        self._generator(&ddstats::SearchPlayer::api(query))
+    }
+
+    fn search_maps(&self, query: &str) -> Result<Vec<ddstats::StatsMap>> {
+        self._generator(&ddstats::StatsMap::search_api(query))
+    }
+
+    fn leaderboard(
+        &self,
+        kind: ddstats::LeaderboardKind,
+        category: Option<Category>,
+        page: u32,
+    ) -> Result<ddstats::Leaderboard> {
+        self._generator(&ddstats::Leaderboard::api(kind, category, page))
+    }
+
+    fn servers(&self) -> Result<Vec<ddstats::StatsServer>> {
+        self._generator(&ddstats::StatsServer::api())
+    }
+
+    fn community_servers(&self, community: &str) -> Result<Vec<ddstats::StatsServer>> {
+        self._generator(&ddstats::StatsServer::api_community(community))
+    }
+}
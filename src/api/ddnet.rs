@@ -20,6 +20,20 @@ pub trait DDnetApi {
         &self,
         latest: usize,
     ) -> impl Future<Output = Result<Vec<LatestFinishes>>> + Send;
+
+    /// Like [`DDnetApi::map`], but always fetches fresh data.
+    ///
+    /// Clients without a response cache can keep the default.
+    fn map_no_cache(&self, map: &str) -> impl Future<Output = Result<Map>> + Send {
+        self.map(map)
+    }
+
+    /// Like [`DDnetApi::player`], but always fetches fresh data.
+    ///
+    /// Clients without a response cache can keep the default.
+    fn player_no_cache(&self, player: &str) -> impl Future<Output = Result<Player>> + Send {
+        self.player(player)
+    }
 }
 
 impl DDnetApi for DDApi {
@@ -156,6 +170,14 @@ impl DDnetApi for DDApi {
         self._generator(&Map::api(map)).await
     }
 
+    async fn map_no_cache(&self, map: &str) -> Result<Map> {
+        self._generator_no_cache(&Map::api(map)).await
+    }
+
+    async fn player_no_cache(&self, player: &str) -> Result<Player> {
+        self._generator_no_cache(&Player::api(player)).await
+    }
+
     /// # Examples
     ///
     /// ```rust,ignore
@@ -230,6 +252,14 @@ impl DDnetApi for DDnetClient {
         self.core()._generator(&Map::api(map)).await
     }
 
+    async fn map_no_cache(&self, map: &str) -> Result<Map> {
+        self.core()._generator_no_cache(&Map::api(map)).await
+    }
+
+    async fn player_no_cache(&self, player: &str) -> Result<Player> {
+        self.core()._generator_no_cache(&Player::api(player)).await
+    }
+
     async fn releases_map(&self) -> Result<Vec<ReleasesMaps>> {
         self.core()._generator_no_cache(&ReleasesMaps::api()).await
     }
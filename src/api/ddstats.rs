@@ -1,6 +1,7 @@
 use crate::api::{DDApi, DDstatsClient, HasApiCore};
 use crate::error::Result;
 use crate::scheme::ddstats::*;
+use crate::scheme::Category;
 use std::future::Future;
 
 pub trait DDstats {
@@ -8,6 +9,20 @@ pub trait DDstats {
     fn map(&self, map: &str) -> impl Future<Output = Result<Map>> + Send;
     fn maps(&self) -> impl Future<Output = Result<Vec<StatsMap>>> + Send;
     fn profile(&self, player: &str) -> impl Future<Output = Result<Profile>> + Send;
+    fn search_players(&self, query: &str)
+        -> impl Future<Output = Result<Vec<SearchPlayer>>> + Send;
+    fn search_maps(&self, query: &str) -> impl Future<Output = Result<Vec<StatsMap>>> + Send;
+    fn leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        category: Option<Category>,
+        page: u32,
+    ) -> impl Future<Output = Result<Leaderboard>> + Send;
+    fn servers(&self) -> impl Future<Output = Result<Vec<StatsServer>>> + Send;
+    fn community_servers(
+        &self,
+        community: &str,
+    ) -> impl Future<Output = Result<Vec<StatsServer>>> + Send;
 }
 
 impl DDstats for DDApi {
@@ -68,6 +83,75 @@ impl DDstats for DDApi {
     async fn profile(&self, player: &str) -> Result<Profile> {
         self._generator(&Profile::api(player)).await
     }
+
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// let api = DDApi::new();
+    /// for player in api.search_players("ByFox").await? {
+    ///     println!("{}: {}", player.name, player.points);
+    /// }
+    /// ```
+    async fn search_players(&self, query: &str) -> Result<Vec<SearchPlayer>> {
+        self._generator(&SearchPlayer::api(query)).await
+    }
+
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// let api = DDApi::new();
+    /// let maps: Vec<StatsMap> = api.search_maps("Fox").await?;
+    /// ```
+    async fn search_maps(&self, query: &str) -> Result<Vec<StatsMap>> {
+        self._generator(&StatsMap::search_api(query)).await
+    }
+
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// let api = DDApi::new();
+    /// let board = api.leaderboard(LeaderboardKind::Points, Some(Category::Brutal), 1).await?;
+    /// for entry in &board.entries {
+    ///     println!("#{} {}: {}", entry.rank, entry.name, entry.points);
+    /// }
+    /// ```
+    async fn leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        category: Option<Category>,
+        page: u32,
+    ) -> Result<Leaderboard> {
+        self._generator(&Leaderboard::api(kind, category, page))
+            .await
+    }
+
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// let api = DDApi::new();
+    /// let servers = api.servers().await?;
+    /// let online: u32 = servers.iter().map(|s| s.num_clients).sum();
+    /// ```
+    async fn servers(&self) -> Result<Vec<StatsServer>> {
+        self._generator(&StatsServer::api()).await
+    }
+
+    async fn community_servers(&self, community: &str) -> Result<Vec<StatsServer>> {
+        self._generator(&StatsServer::api_community(community))
+            .await
+    }
 }
 
 impl DDstats for DDstatsClient {
@@ -75,10 +159,74 @@ impl DDstats for DDstatsClient {
         self.core()._generator(&Player::api(player)).await
     }
 
+    /// # Examples
+    ///
+    /// The checkpoint keys of `time_cps` rows are part of the schema; other
+    /// keys next to them are drift.
+    ///
+    /// ```rust
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// struct Tutorial;
+    ///
+    /// impl Transport for Tutorial {
+    ///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+    ///         Box::pin(async {
+    ///             Ok(HttpResponse::ok(
+    ///                 r#"{"info": {"map": {"map": "Tutorial", "server": "Novice", "points": 1, "stars": 1, "mapper": "Pipou", "timestamp": null},
+    ///                              "finishes": 1, "finishes_rank": 1, "median_time": 30.0},
+    ///                     "rankings": [], "team_rankings": [], "playtime": [],
+    ///                     "time_cps": [{"name": "nameless tee", "cp1": 4.5, "cp2": 9.25, "time": 30.0, "speed": 12}]}"#,
+    ///             ))
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let report = DriftReport::new();
+    /// let mut api = DDstatsClient::new_with_transport(Tutorial);
+    /// api.set_drift_report(report.clone());
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let map = api.map("Tutorial").await.unwrap();
+    /// assert_eq!(map.time_cps[0].splits(), &[4.5, 9.25]);
+    /// # });
+    /// let drifts = report.drifts();
+    /// assert_eq!(drifts.len(), 1);
+    /// assert_eq!((drifts[0].path.as_str(), &drifts[0].kind), ("time_cps[].speed", &DriftKind::UnknownField));
+    /// ```
     async fn map(&self, map: &str) -> Result<Map> {
         self.core()._generator(&Map::api(map)).await
     }
 
+    /// # Examples
+    ///
+    /// Timestamps arrive as numbers or strings; neither counts as schema drift.
+    ///
+    /// ```rust
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddstats::*;
+    ///
+    /// struct Maps;
+    ///
+    /// impl Transport for Maps {
+    ///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+    ///         Box::pin(async {
+    ///             Ok(HttpResponse::ok(
+    ///                 r#"[{"map": "Fox", "server": "Novice", "points": 2, "stars": 2, "mapper": "ByFox", "timestamp": 1715025600},
+    ///                     {"map": "Kobra", "server": "Moderate", "points": 5, "stars": 1, "mapper": "Zerodin", "timestamp": "2024-05-06 20:00:00"}]"#,
+    ///             ))
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let report = DriftReport::new();
+    /// let mut api = DDstatsClient::new_with_transport(Maps);
+    /// api.set_drift_report(report.clone());
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// assert_eq!(api.maps().await.unwrap().len(), 2);
+    /// # });
+    /// assert!(report.is_empty(), "{report}");
+    /// ```
     async fn maps(&self) -> Result<Vec<StatsMap>> {
         self.core()._generator(&StatsMap::api()).await
     }
@@ -86,4 +234,33 @@ impl DDstats for DDstatsClient {
     async fn profile(&self, player: &str) -> Result<Profile> {
         self.core()._generator(&Profile::api(player)).await
     }
+
+    async fn search_players(&self, query: &str) -> Result<Vec<SearchPlayer>> {
+        self.core()._generator(&SearchPlayer::api(query)).await
+    }
+
+    async fn search_maps(&self, query: &str) -> Result<Vec<StatsMap>> {
+        self.core()._generator(&StatsMap::search_api(query)).await
+    }
+
+    async fn leaderboard(
+        &self,
+        kind: LeaderboardKind,
+        category: Option<Category>,
+        page: u32,
+    ) -> Result<Leaderboard> {
+        self.core()
+            ._generator(&Leaderboard::api(kind, category, page))
+            .await
+    }
+
+    async fn servers(&self) -> Result<Vec<StatsServer>> {
+        self.core()._generator(&StatsServer::api()).await
+    }
+
+    async fn community_servers(&self, community: &str) -> Result<Vec<StatsServer>> {
+        self.core()
+            ._generator(&StatsServer::api_community(community))
+            .await
+    }
 }
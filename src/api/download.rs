@@ -0,0 +1,297 @@
+use crate::api::{ApiCore, DDApi, DDnetClient, HasApiCore};
+use crate::error::{Endpoint, Error, Result};
+use crate::prelude::{encode, is_sha256};
+use crate::scheme::ddnet::prelude::{IMap, Map};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Default upper bound for a single map download.
+pub const DEFAULT_MAX_MAP_SIZE: u64 = 64 * 1024 * 1024;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Content-addressed directory of downloaded maps.
+///
+/// Maps are stored as `<root>/<sha256>.map`, so identical content is only
+/// ever downloaded and stored once. Downloads by name additionally record the
+/// sha256 they resolved to under `<root>/names/`.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::download::MapStore;
+///
+/// let store = MapStore::new("/tmp/maps").with_max_size(8 * 1024 * 1024);
+/// assert_eq!(store.max_size(), 8 * 1024 * 1024);
+///
+/// let sha256 = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+/// assert_eq!(
+///     store.path(sha256).unwrap(),
+///     std::path::Path::new("/tmp/maps/9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08.map")
+/// );
+/// assert_eq!(store.path("../../etc/passwd"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MapStore {
+    root: PathBuf,
+    max_size: u64,
+}
+
+impl MapStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_size: DEFAULT_MAX_MAP_SIZE,
+        }
+    }
+
+    /// Sets the size limit applied to downloads without a known size.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Where a map with the given sha256 is (or would be) stored.
+    ///
+    /// `None` unless `sha256` is 64 hex digits, so a reported hash can never
+    /// point outside the store.
+    pub fn path(&self, sha256: &str) -> Option<PathBuf> {
+        is_sha256(sha256).then(|| {
+            self.root
+                .join(format!("{}.map", sha256.to_ascii_lowercase()))
+        })
+    }
+
+    /// Path of an already stored map.
+    pub async fn get(&self, sha256: &str) -> Option<PathBuf> {
+        let path = self.path(sha256)?;
+        fs::try_exists(&path)
+            .await
+            .ok()
+            .filter(|exists| *exists)
+            .map(|_| path)
+    }
+
+    /// Path of a map previously downloaded by name.
+    pub async fn get_by_name(&self, name: &str) -> Option<PathBuf> {
+        let sha256 = fs::read_to_string(self.name_path(name)).await.ok()?;
+        self.get(sha256.trim()).await
+    }
+
+    fn name_path(&self, name: &str) -> PathBuf {
+        self.root
+            .join("names")
+            .join(format!("{}.sha256", encode(name)))
+    }
+}
+
+pub trait MapDownload {
+    /// Downloads the exact map version a server runs, verifying its sha256.
+    ///
+    /// Returns immediately if the store already holds that sha256. Falls back
+    /// to [`MapDownload::download_map_by_name`] when the server did not report
+    /// one, or reported something that is not a sha256.
+    fn download_map(
+        &self,
+        map: &IMap,
+        store: &MapStore,
+    ) -> impl Future<Output = Result<PathBuf>> + Send;
+
+    /// Downloads the current release of a map by name.
+    ///
+    /// Repeated calls are served from the store; use
+    /// [`MapDownload::refresh_map_by_name`] to pick up a newer release.
+    fn download_map_by_name(
+        &self,
+        name: &str,
+        store: &MapStore,
+    ) -> impl Future<Output = Result<PathBuf>> + Send;
+
+    /// Like [`MapDownload::download_map_by_name`], but always downloads.
+    fn refresh_map_by_name(
+        &self,
+        name: &str,
+        store: &MapStore,
+    ) -> impl Future<Output = Result<PathBuf>> + Send;
+}
+
+async fn download_map(core: &ApiCore, map: &IMap, store: &MapStore) -> Result<PathBuf> {
+    let (Some(url), Some(expected)) = (map.download_url(), map.valid_sha256()) else {
+        return download_by_name(core, &map.name, store, false).await;
+    };
+
+    if let Some(path) = store.get(expected).await {
+        return Ok(path);
+    }
+
+    let limit = map
+        .size
+        .and_then(|size| u64::try_from(size).ok())
+        .map_or(store.max_size, |size| size.min(store.max_size));
+    fetch_into_store(core, &url, store, limit, Some(expected)).await
+}
+
+async fn download_by_name(
+    core: &ApiCore,
+    name: &str,
+    store: &MapStore,
+    refresh: bool,
+) -> Result<PathBuf> {
+    if !refresh {
+        if let Some(path) = store.get_by_name(name).await {
+            return Ok(path);
+        }
+    }
+
+    let url = Map::download_url_with_name(name);
+    let path = fetch_into_store(core, &url, store, store.max_size, None).await?;
+
+    let index = store.name_path(name);
+    if let Some(parent) = index.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let sha256 = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string();
+    fs::write(index, sha256).await?;
+    Ok(path)
+}
+
+/// Streams `url` into a temporary file, hashing as it goes, then moves it to
+/// its content address. The temporary file is removed on any failure.
+async fn fetch_into_store(
+    core: &ApiCore,
+    url: &str,
+    store: &MapStore,
+    limit: u64,
+    expected: Option<&str>,
+) -> Result<PathBuf> {
+    fs::create_dir_all(&store.root).await?;
+    let temp = store.root.join(format!(
+        ".{}-{}.part",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = stream_to_file(core, url, &temp, limit).await;
+    let actual = match result {
+        Ok(actual) => actual,
+        Err(e) => {
+            let _ = fs::remove_file(&temp).await;
+            return Err(e);
+        }
+    };
+
+    if let Some(expected) = expected {
+        if !expected.eq_ignore_ascii_case(&actual) {
+            let _ = fs::remove_file(&temp).await;
+            return Err(Error::ChecksumMismatch {
+                expected: expected.to_ascii_lowercase(),
+                actual,
+            });
+        }
+    }
+
+    let Some(path) = store.path(&actual) else {
+        let _ = fs::remove_file(&temp).await;
+        return Err(Error::Format(format!("invalid sha256 {actual}")));
+    };
+    fs::rename(&temp, &path).await?;
+    Ok(path)
+}
+
+async fn stream_to_file(core: &ApiCore, url: &str, path: &Path, limit: u64) -> Result<String> {
+    let mut response = core.open_download(url).await?;
+    if response.content_length.is_some_and(|len| len > limit) {
+        return Err(Error::TooLarge { limit });
+    }
+
+    let mut file = fs::File::create(path).await?;
+    let mut hasher = Sha256::new();
+    let mut written: u64 = 0;
+    while let Some(chunk) = response.chunk().await? {
+        written += chunk.len() as u64;
+        if written > limit {
+            return Err(Error::TooLarge { limit });
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    if written == 0 {
+        return Err(Error::EmptyBody {
+            endpoint: Endpoint::from_url(url),
+            url: url.to_string(),
+        });
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+impl MapDownload for DDApi {
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::download::*;
+    ///
+    /// let api = DDApi::new();
+    /// let store = MapStore::new("maps");
+    /// let master = api.master().await?;
+    /// let server = &master.servers[0];
+    /// let path = api.download_map(&server.info.map, &store).await?;
+    /// println!("{} -> {}", server.info.map.name, path.display());
+    /// ```
+    async fn download_map(&self, map: &IMap, store: &MapStore) -> Result<PathBuf> {
+        download_map(self.core(), map, store).await
+    }
+
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::download::*;
+    ///
+    /// let api = DDApi::new();
+    /// let path = api.download_map_by_name("Tutorial", &MapStore::new("maps")).await?;
+    /// ```
+    async fn download_map_by_name(&self, name: &str, store: &MapStore) -> Result<PathBuf> {
+        download_by_name(self.core(), name, store, false).await
+    }
+
+    async fn refresh_map_by_name(&self, name: &str, store: &MapStore) -> Result<PathBuf> {
+        download_by_name(self.core(), name, store, true).await
+    }
+}
+
+impl MapDownload for DDnetClient {
+    async fn download_map(&self, map: &IMap, store: &MapStore) -> Result<PathBuf> {
+        download_map(self.core(), map, store).await
+    }
+
+    async fn download_map_by_name(&self, name: &str, store: &MapStore) -> Result<PathBuf> {
+        download_by_name(self.core(), name, store, false).await
+    }
+
+    async fn refresh_map_by_name(&self, name: &str, store: &MapStore) -> Result<PathBuf> {
+        download_by_name(self.core(), name, store, true).await
+    }
+}
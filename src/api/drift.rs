@@ -0,0 +1,522 @@
+use crate::error::{Endpoint, Error, Result};
+#[cfg(feature = "ddstats")]
+use crate::scheme::ddstats::{Checkpoints, RecentFinish, TimeCpsSMap};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// How a response differs from the crate's schema.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DriftKind {
+    /// The API sent a field the schema does not know; it was dropped.
+    UnknownField,
+    /// The API no longer sends a field; the schema filled in its default.
+    MissingField,
+    /// A value no longer deserializes, e.g. it changed its type; it was
+    /// dropped with this message, along with the struct or list entry that
+    /// required it.
+    Invalid(String),
+}
+
+/// One difference between a response and the schema.
+///
+/// Array indices in `path` are left out (`servers[].info`), so a change is
+/// reported once per endpoint rather than once per element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Drift {
+    pub endpoint: Endpoint,
+    /// The first URL the drift was seen on.
+    pub url: String,
+    pub path: String,
+    pub kind: DriftKind,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = &self.path;
+        match &self.kind {
+            DriftKind::UnknownField => write!(f, "{}: unknown field `{path}`", self.endpoint),
+            DriftKind::MissingField => write!(f, "{}: missing field `{path}`", self.endpoint),
+            DriftKind::Invalid(msg) => write!(f, "{}: `{path}` is invalid: {msg}", self.endpoint),
+        }
+    }
+}
+
+type Callback = Arc<dyn Fn(&Drift) + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    seen: HashSet<(Endpoint, String, DriftKind)>,
+    drifts: Vec<Drift>,
+}
+
+/// Collects schema drift of the responses a client parses.
+///
+/// Opt-in with `set_drift_report` on a client: each JSON response is then
+/// compared with the fields the schema asks for while deserializing it.
+/// Unknown fields and fields the schema had to default are recorded without
+/// failing the call. So are invalid values: the response is parsed again
+/// without them, which leaves an optional field empty or drops the list
+/// entry that needed the value. The call only fails when the top-level value
+/// itself is invalid or more than 16 values had to be dropped. Values the schema
+/// accepts in several shapes (like a timestamp sent as a number or a string)
+/// are not drift. Each drift is recorded once. Clones share the same report,
+/// so one report can watch several clients.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+///
+/// struct Changed;
+///
+/// impl Transport for Changed {
+///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async {
+///             Ok(HttpResponse::ok(
+///                 r#"[{"name": "Tutorial", "website": "", "thumbnail": "", "web_preview": "", "type": "Novice",
+///                     "points": 1, "difficulty": 1, "mapper": "Pipou", "release": "2015-06-27",
+///                     "width": 100, "height": 50, "stars": 1}]"#,
+///             ))
+///         })
+///     }
+/// }
+///
+/// let report = DriftReport::new().with_callback(|drift| eprintln!("warning: {drift}"));
+/// let mut api = DDnetClient::new_with_transport(Changed);
+/// api.set_drift_report(report.clone());
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let releases = api.releases_map().await.unwrap();
+/// assert!(releases[0].tiles.is_empty());
+/// # });
+/// let drifts = report.drifts();
+/// assert_eq!(drifts.len(), 2);
+/// assert_eq!((drifts[0].path.as_str(), &drifts[0].kind), ("[].stars", &DriftKind::UnknownField));
+/// assert_eq!((drifts[1].path.as_str(), &drifts[1].kind), ("[].tiles", &DriftKind::MissingField));
+/// assert_eq!(report.to_string(), "releases:\n  unknown field `[].stars`\n  missing field `[].tiles`\n");
+/// ```
+///
+/// A finish whose time changed its type is dropped and reported, the rest
+/// of the response is still returned:
+/// ```rust
+/// use ddapi_rs::prelude::*;
+///
+/// struct Retyped;
+///
+/// impl Transport for Retyped {
+///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async {
+///             Ok(HttpResponse::ok(
+///                 r#"[{"timestamp": 1715025600, "map": "Tutorial", "name": "nameless tee", "time": "30.0", "server": "GER"},
+///                     {"timestamp": 1715025601, "map": "Tutorial", "name": "brainless tee", "time": 31.0, "server": "GER"}]"#,
+///             ))
+///         })
+///     }
+/// }
+///
+/// let report = DriftReport::new();
+/// let mut api = DDnetClient::new_with_transport(Retyped);
+/// api.set_drift_report(report.clone());
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let finishes = api.latest_finish().await.unwrap();
+/// assert_eq!(finishes.len(), 1);
+/// assert_eq!(finishes[0].name, "brainless tee");
+/// # });
+/// let drifts = report.drifts();
+/// assert_eq!(drifts.len(), 1);
+/// assert_eq!(drifts[0].path, "[].time");
+/// assert!(matches!(&drifts[0].kind, DriftKind::Invalid(msg) if msg.starts_with("invalid type")));
+/// ```
+#[derive(Clone, Default)]
+pub struct DriftReport {
+    state: Arc<Mutex<State>>,
+    callback: Option<Callback>,
+}
+
+impl fmt::Debug for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriftReport")
+            .field("drifts", &self.drifts())
+            .finish()
+    }
+}
+
+impl DriftReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` for every new drift, e.g. to log it as a warning.
+    pub fn with_callback(mut self, callback: impl Fn(&Drift) + Send + Sync + 'static) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// All drifts recorded so far, in the order they were seen.
+    pub fn drifts(&self) -> Vec<Drift> {
+        self.lock().drifts.clone()
+    }
+
+    pub fn by_endpoint(&self) -> BTreeMap<Endpoint, Vec<Drift>> {
+        let mut grouped: BTreeMap<Endpoint, Vec<Drift>> = BTreeMap::new();
+        for drift in self.drifts() {
+            grouped.entry(drift.endpoint).or_default().push(drift);
+        }
+        grouped
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().drifts.is_empty()
+    }
+
+    /// Forgets all drifts, so they are reported again when seen.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.seen.clear();
+        state.drifts.clear();
+    }
+
+    /// Deserializes the JSON `body` of `url`, recording how it differs from `T`.
+    ///
+    /// Each invalid value is skipped in a new pass; when that makes its parent
+    /// fail, the parent is skipped instead, until the value fits the schema.
+    pub(crate) fn deserialize<T: DeserializeOwned>(&self, url: &str, body: &[u8]) -> Result<T> {
+        let raw: Value = serde_json::from_slice(body).map_err(|e| Error::from(e).with_url(url))?;
+        let mut skip: HashSet<Vec<Segment>> = HashSet::new();
+        let mut found = Vec::new();
+        let mut dropped = 0;
+        let result = loop {
+            let pass = Pass {
+                skip,
+                ..Default::default()
+            };
+            let result = serde_path_to_error::deserialize(Tracked {
+                value: &raw,
+                at: Vec::new(),
+                pass: &pass,
+            })
+            .map_err(|e| Error::from(e).with_url(url));
+
+            let failed = pass.failed.into_inner().unwrap_or_default();
+            skip = pass.skip;
+            match result {
+                Ok(value) => {
+                    found.extend(pass.found.into_inner());
+                    break Ok(value);
+                }
+                Err(Error::Json { ref source, .. }) => {
+                    // A parent failing for a skipped child is not a new drift.
+                    if !skip.iter().any(|skipped| skipped.starts_with(&failed)) {
+                        found.push((
+                            display_path(&failed),
+                            DriftKind::Invalid(source.to_string()),
+                        ));
+                        dropped += 1;
+                    }
+                    if failed.is_empty() || dropped > MAX_DROPPED {
+                        break result;
+                    }
+                    skip.insert(failed);
+                }
+                Err(e) => break Err(e),
+            }
+        };
+
+        let endpoint = Endpoint::from_url(url);
+        for (path, kind) in found {
+            self.record(Drift {
+                endpoint,
+                url: url.to_string(),
+                path,
+                kind,
+            });
+        }
+        result
+    }
+
+    fn record(&self, drift: Drift) {
+        let key = (drift.endpoint, drift.path.clone(), drift.kind.clone());
+        {
+            let mut state = self.lock();
+            if !state.seen.insert(key) {
+                return;
+            }
+            state.drifts.push(drift.clone());
+        }
+        if let Some(callback) = &self.callback {
+            callback(&drift);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// One block per endpoint, one indented line per drift.
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (endpoint, drifts) in self.by_endpoint() {
+            writeln!(f, "{endpoint}:")?;
+            for drift in drifts {
+                let line = drift.to_string();
+                let line = line.strip_prefix(&format!("{endpoint}: ")).unwrap_or(&line);
+                writeln!(f, "  {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How many invalid values a response may drop before the call fails.
+const MAX_DROPPED: usize = 16;
+
+/// One step of the path to a value in the original response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Formats `servers[3].info` as `servers[].info`.
+fn display_path(at: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in at {
+        match segment {
+            Segment::Key(key) if out.is_empty() => out.push_str(key),
+            Segment::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            Segment::Index(_) => out.push_str("[]"),
+        }
+    }
+    out
+}
+
+fn child(at: &[Segment], segment: Segment) -> Vec<Segment> {
+    let mut child = at.to_vec();
+    child.push(segment);
+    child
+}
+
+/// State of one deserialization pass over a response.
+#[derive(Default)]
+struct Pass {
+    /// Values treated as absent.
+    skip: HashSet<Vec<Segment>>,
+    found: RefCell<Vec<(String, DriftKind)>>,
+    /// The innermost value that failed to deserialize.
+    failed: RefCell<Option<Vec<Segment>>>,
+}
+
+type Flattened = (&'static [&'static str], fn(&str) -> bool);
+
+/// Structs with a `#[serde(flatten)]` field are deserialized as maps, so
+/// `deserialize_struct` never sees their fields. Given such a struct's
+/// `expecting` message, returns its own fields and the keys its flattened
+/// fields take.
+fn flattened(expecting: &str) -> Option<Flattened> {
+    match expecting {
+        #[cfg(feature = "ddstats")]
+        "struct RecentFinish" => Some((RecentFinish::FIELDS, Checkpoints::is_key)),
+        #[cfg(feature = "ddstats")]
+        "struct TimeCpsSMap" => Some((TimeCpsSMap::FIELDS, Checkpoints::is_key)),
+        _ => None,
+    }
+}
+
+/// Deserializes a parsed response, noting where the fields a struct asks for
+/// differ from the keys the response has.
+struct Tracked<'de> {
+    value: &'de Value,
+    at: Vec<Segment>,
+    pass: &'de Pass,
+}
+
+impl Tracked<'_> {
+    /// Runs `f`, blaming this value for an error unless a value inside it failed first.
+    fn tracked<T>(self, f: impl FnOnce(Self) -> serde_json::Result<T>) -> serde_json::Result<T> {
+        let pass = self.pass;
+        let at = self.at.clone();
+        let result = f(self);
+        let mut failed = pass.failed.borrow_mut();
+        match &result {
+            // An error inside was recovered from, e.g. by an untagged enum.
+            Ok(_) => *failed = None,
+            Err(_) => {
+                failed.get_or_insert(at);
+            }
+        }
+        result
+    }
+
+    /// Notes the keys of an object that `fields` does not list, and the
+    /// fields it lacks. Keys matching `extra` are expected but optional.
+    fn check_keys(&self, fields: &[&str], extra: impl Fn(&str) -> bool) {
+        let Value::Object(map) = self.value else {
+            return;
+        };
+        let mut found = self.pass.found.borrow_mut();
+        for key in map.keys() {
+            if !fields.contains(&key.as_str()) && !extra(key) {
+                let path = child(&self.at, Segment::Key(key.clone()));
+                found.push((display_path(&path), DriftKind::UnknownField));
+            }
+        }
+        for field in fields.iter().filter(|field| !map.contains_key(**field)) {
+            let path = child(&self.at, Segment::Key(field.to_string()));
+            found.push((display_path(&path), DriftKind::MissingField));
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Tracked<'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        self.tracked(|this| match this.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(u), _, _) => visitor.visit_u64(u),
+                (_, Some(i), _) => visitor.visit_i64(i),
+                (_, _, f) => visitor.visit_f64(f.unwrap_or(f64::NAN)),
+            },
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Array(items) => visitor.visit_seq(Seq {
+                items: items.iter().enumerate(),
+                at: this.at,
+                pass: this.pass,
+            }),
+            Value::Object(map) => visitor.visit_map(Map {
+                entries: map.iter(),
+                next: None,
+                at: this.at,
+                pass: this.pass,
+            }),
+        })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        self.tracked(|this| match this.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(this),
+        })
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        self.tracked(|this| visitor.visit_newtype_struct(this))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        self.check_keys(fields, |_| false);
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        if let Some((fields, extra)) = flattened(&(&visitor as &dyn de::Expected).to_string()) {
+            self.check_keys(fields, extra);
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> serde_json::Result<V::Value> {
+        self.tracked(|this| this.value.deserialize_enum(name, variants, visitor))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> serde_json::Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct identifier
+    }
+}
+
+struct Seq<'de> {
+    items: std::iter::Enumerate<std::slice::Iter<'de, Value>>,
+    at: Vec<Segment>,
+    pass: &'de Pass,
+}
+
+impl<'de> SeqAccess<'de> for Seq<'de> {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> serde_json::Result<Option<T::Value>> {
+        let pass = self.pass;
+        let at = &self.at;
+        self.items
+            .by_ref()
+            .map(|(i, value)| (child(at, Segment::Index(i)), value))
+            .find(|(at, _)| !pass.skip.contains(at))
+            .map(|(at, value)| seed.deserialize(Tracked { value, at, pass }))
+            .transpose()
+    }
+}
+
+struct Map<'de> {
+    entries: serde_json::map::Iter<'de>,
+    next: Option<(Vec<Segment>, &'de Value)>,
+    at: Vec<Segment>,
+    pass: &'de Pass,
+}
+
+impl<'de> MapAccess<'de> for Map<'de> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> serde_json::Result<Option<K::Value>> {
+        let pass = self.pass;
+        let at = &self.at;
+        let next = self
+            .entries
+            .by_ref()
+            .map(|(key, value)| (key, child(at, Segment::Key(key.clone())), value))
+            .find(|(_, at, _)| !pass.skip.contains(at));
+        self.next = next.as_ref().map(|(_, at, value)| (at.clone(), *value));
+        next.map(|(key, _, _)| seed.deserialize(BorrowedStrDeserializer::new(key)))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> serde_json::Result<V::Value> {
+        let (at, value) = self
+            .next
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before its key"))?;
+        seed.deserialize(Tracked {
+            value,
+            at,
+            pass: self.pass,
+        })
+    }
+}
@@ -0,0 +1,200 @@
+use crate::api::{ApiCore, DDApi, DDnetClient, HasApiCore};
+use crate::error::Result;
+use crate::scheme::ddnet::prelude::{Feed, FeedEntry, RecordEntry, ReleaseEntry};
+use futures_util::stream::{self, Stream};
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::time::Duration;
+
+/// Default time between two polls of a feed.
+pub const DEFAULT_FEED_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub trait DDnetFeeds {
+    /// Fetches and parses any RSS or Atom feed.
+    fn feed(&self, url: &str) -> impl Future<Output = Result<Feed>> + Send;
+    /// New map records, newest first. Entries that do not parse as records are skipped.
+    fn records_feed(&self) -> impl Future<Output = Result<Vec<RecordEntry>>> + Send;
+    /// New map releases, newest first.
+    fn releases_feed(&self) -> impl Future<Output = Result<Vec<ReleaseEntry>>> + Send;
+}
+
+async fn fetch_feed(core: &ApiCore, url: &str) -> Result<Feed> {
+    let body = core.send_request(url).await?;
+    Feed::parse(&String::from_utf8_lossy(&body))
+}
+
+impl DDnetFeeds for DDApi {
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddnet::DDnetFeeds;
+    ///
+    /// let api = DDApi::new();
+    /// for record in api.records_feed().await? {
+    ///     println!("{}: {} ({:?})", record.map, record.time, record.improvement());
+    /// }
+    /// ```
+    async fn feed(&self, url: &str) -> Result<Feed> {
+        fetch_feed(self.core(), url).await
+    }
+
+    async fn records_feed(&self) -> Result<Vec<RecordEntry>> {
+        let feed = fetch_feed(self.core(), &RecordEntry::api()).await?;
+        Ok(feed
+            .entries
+            .iter()
+            .filter_map(RecordEntry::from_entry)
+            .collect())
+    }
+
+    async fn releases_feed(&self) -> Result<Vec<ReleaseEntry>> {
+        let feed = fetch_feed(self.core(), &ReleaseEntry::api()).await?;
+        Ok(feed
+            .entries
+            .iter()
+            .filter_map(ReleaseEntry::from_entry)
+            .collect())
+    }
+}
+
+impl DDnetFeeds for DDnetClient {
+    async fn feed(&self, url: &str) -> Result<Feed> {
+        fetch_feed(self.core(), url).await
+    }
+
+    async fn records_feed(&self) -> Result<Vec<RecordEntry>> {
+        let feed = fetch_feed(self.core(), &RecordEntry::api()).await?;
+        Ok(feed
+            .entries
+            .iter()
+            .filter_map(RecordEntry::from_entry)
+            .collect())
+    }
+
+    async fn releases_feed(&self) -> Result<Vec<ReleaseEntry>> {
+        let feed = fetch_feed(self.core(), &ReleaseEntry::api()).await?;
+        Ok(feed
+            .entries
+            .iter()
+            .filter_map(ReleaseEntry::from_entry)
+            .collect())
+    }
+}
+
+/// Polls a feed and yields each entry once, oldest first.
+///
+/// Entries are recognised by their id. Only the ids of the latest poll are
+/// remembered, since entries that dropped out of a feed do not come back.
+///
+/// # Examples
+/// ```rust,ignore
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::ddnet::FeedWatcher;
+/// use ddapi_rs::prelude::stream::StreamExt;
+///
+/// let records = FeedWatcher::records(DDApi::new()).skip_existing().await?;
+/// let mut records = std::pin::pin!(records.into_stream());
+/// while let Some(record) = records.next().await {
+///     let record = record?;
+///     println!("{} took the record on {} from {:?}", record.players.join(" & "), record.map, record.previous_holders);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FeedWatcher<A, T> {
+    api: A,
+    url: String,
+    interval: Duration,
+    seen: HashSet<String>,
+    parse: fn(&FeedEntry) -> Option<T>,
+}
+
+impl<A: DDnetFeeds + Send + Sync> FeedWatcher<A, FeedEntry> {
+    /// Watches any feed, yielding raw entries.
+    pub fn new(api: A, url: impl Into<String>) -> Self {
+        Self::with_parser(api, url, |entry| Some(entry.clone()))
+    }
+}
+
+impl<A: DDnetFeeds + Send + Sync> FeedWatcher<A, RecordEntry> {
+    pub fn records(api: A) -> Self {
+        Self::with_parser(api, RecordEntry::api(), RecordEntry::from_entry)
+    }
+}
+
+impl<A: DDnetFeeds + Send + Sync> FeedWatcher<A, ReleaseEntry> {
+    pub fn releases(api: A) -> Self {
+        Self::with_parser(api, ReleaseEntry::api(), ReleaseEntry::from_entry)
+    }
+}
+
+impl<A: DDnetFeeds + Send + Sync, T: Send> FeedWatcher<A, T> {
+    /// Watches `url`, turning entries into `T` with `parse`; entries it rejects are skipped.
+    pub fn with_parser(api: A, url: impl Into<String>, parse: fn(&FeedEntry) -> Option<T>) -> Self {
+        Self {
+            api,
+            url: url.into(),
+            interval: DEFAULT_FEED_INTERVAL,
+            seen: HashSet::new(),
+            parse,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Marks `ids` as already seen, e.g. ids saved before a restart.
+    pub fn with_seen(mut self, ids: impl IntoIterator<Item = String>) -> Self {
+        self.seen.extend(ids);
+        self
+    }
+
+    /// Ids of the entries seen in the latest poll.
+    pub fn seen(&self) -> &HashSet<String> {
+        &self.seen
+    }
+
+    /// Fetches the feed once and marks everything in it as seen, so only later entries are yielded.
+    pub async fn skip_existing(mut self) -> Result<Self> {
+        self.poll().await?;
+        Ok(self)
+    }
+
+    /// Fetches the feed once and returns the unseen entries, oldest first.
+    pub async fn poll(&mut self) -> Result<Vec<T>> {
+        let feed = self.api.feed(&self.url).await?;
+        let new = feed
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| !self.seen.contains(&entry.id))
+            .filter_map(self.parse)
+            .collect();
+        self.seen = feed.entries.into_iter().map(|entry| entry.id).collect();
+        Ok(new)
+    }
+
+    /// Turns the watcher into an endless stream of new entries.
+    ///
+    /// A failed poll yields its error and is retried after the next interval.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T>> + Send {
+        let state = (self, VecDeque::new(), true);
+        stream::unfold(state, |(mut watcher, mut pending, mut first)| async move {
+            loop {
+                if let Some(entry) = pending.pop_front() {
+                    return Some((Ok(entry), (watcher, pending, first)));
+                }
+                if !first {
+                    tokio::time::sleep(watcher.interval).await;
+                }
+                first = false;
+                match watcher.poll().await {
+                    Ok(new) => pending.extend(new),
+                    Err(e) => return Some((Err(e), (watcher, pending, first))),
+                }
+            }
+        })
+    }
+}
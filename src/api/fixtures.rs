@@ -0,0 +1,226 @@
+use crate::api::{HttpRequest, HttpResponse, ReqwestTransport, Transport, TransportFuture};
+use crate::error::{Error, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// One recorded request and its response, stored as a JSON file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: FixtureBody,
+}
+
+/// Response bodies are kept as text when they are UTF-8, so fixtures stay readable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FixtureBody {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Fixture {
+    pub fn new(url: impl Into<String>, response: &HttpResponse) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = match String::from_utf8(response.body.clone()) {
+            Ok(text) => FixtureBody::Text(text),
+            Err(e) => FixtureBody::Bytes(e.into_bytes()),
+        };
+        Self {
+            url: url.into(),
+            status: response.status.as_u16(),
+            headers,
+            body,
+        }
+    }
+
+    /// File name the fixture for `url` is stored under: a readable prefix plus a hash of the whole URL.
+    pub fn file_name(url: &str) -> String {
+        let readable: String = url
+            .split_once("://")
+            .map_or(url, |(_, rest)| rest)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(80)
+            .collect();
+        format!("{readable}-{:016x}.json", fnv1a(url.as_bytes()))
+    }
+
+    pub fn to_response(&self) -> Result<HttpResponse> {
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|_| Error::Format(format!("invalid status {} in fixture", self.status)))?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name.as_str()),
+                HeaderValue::try_from(value.as_str()),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        let body = match &self.body {
+            FixtureBody::Text(text) => text.clone().into_bytes(),
+            FixtureBody::Bytes(bytes) => bytes.clone(),
+        };
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(Self::file_name(&self.url));
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Passes requests on to another transport and saves every response to a fixture directory.
+///
+/// A later request for the same URL overwrites its fixture. Non-success
+/// responses are recorded too, so error handling can be replayed.
+///
+/// # Examples
+/// ```rust,ignore
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::fixtures::RecordingTransport;
+///
+/// let api = DDApi::new_with_transport(RecordingTransport::reqwest("tests/fixtures"));
+/// api.player("nameless tee").await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordingTransport<T = ReqwestTransport> {
+    inner: T,
+    dir: PathBuf,
+}
+
+impl RecordingTransport {
+    /// Records real responses fetched with the crate's default reqwest client.
+    pub fn reqwest(dir: impl Into<PathBuf>) -> Self {
+        Self::new(ReqwestTransport::with_defaults(), dir)
+    }
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+        Box::pin(async move {
+            let url = request.url.clone();
+            let response = self.inner.send(request).await?;
+            Fixture::new(url, &response).save(&self.dir)?;
+            Ok(response)
+        })
+    }
+}
+
+/// Serves responses recorded by [`RecordingTransport`], without touching the network.
+///
+/// Requests are matched by URL only, as the transport receives it after any
+/// [`BaseUrls`](crate::api::BaseUrls) rewrite. A request without a fixture
+/// fails with [`Error::MissingFixture`], which names the URL the crate built.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::fixtures::{RecordingTransport, ReplayTransport};
+///
+/// struct Server;
+///
+/// impl Transport for Server {
+///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async { Ok(HttpResponse::ok(r#"{"communities": [], "servers": []}"#)) })
+///     }
+/// }
+///
+/// let dir = std::env::temp_dir().join(format!("ddapi-fixtures-{}", std::process::id()));
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let recorder = DDnetClient::new_with_transport(RecordingTransport::new(Server, &dir));
+/// let recorded = recorder.master().await.unwrap();
+///
+/// let replay = DDnetClient::new_with_transport(ReplayTransport::open(&dir).unwrap());
+/// assert_eq!(replay.master().await.unwrap(), recorded);
+/// assert!(matches!(replay.status().await, Err(Error::MissingFixture(_))));
+///
+/// // The error names the URL the crate built, not where `BaseUrls` sent it.
+/// let mut moved = DDnetClient::new_with_transport(ReplayTransport::default());
+/// moved.set_base_urls(BaseUrls::all("http://127.0.0.1:8080"));
+/// let error = moved.status().await.unwrap_err();
+/// assert_eq!(error.url(), Some("https://ddnet.org/status/json/stats.json"));
+/// # });
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReplayTransport {
+    fixtures: HashMap<String, Fixture>,
+}
+
+impl ReplayTransport {
+    /// Loads every `*.json` fixture in `dir`.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let mut replay = Self::default();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let fixture: Fixture = serde_json::from_slice(&std::fs::read(&path)?)?;
+                replay.insert(fixture);
+            }
+        }
+        Ok(replay)
+    }
+
+    /// Adds or replaces the fixture for its URL.
+    pub fn insert(&mut self, fixture: Fixture) {
+        self.fixtures.insert(fixture.url.clone(), fixture);
+    }
+
+    pub fn get(&self, url: &str) -> Option<&Fixture> {
+        self.fixtures.get(url)
+    }
+
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.fixtures.keys().map(String::as_str)
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+        Box::pin(async move {
+            self.get(&request.url)
+                .ok_or(Error::MissingFixture(request.url))?
+                .to_response()
+        })
+    }
+}
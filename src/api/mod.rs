@@ -1,24 +1,45 @@
-use crate::error::{Error, Result};
+use crate::error::{Endpoint, Error, Result};
+pub use drift::*;
 #[cfg(feature = "cache")]
 use moka::future::Cache;
 use reqwest::header;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-#[allow(unused_imports)]
+use std::sync::Arc;
 use std::time::Duration;
+pub use transport::*;
+
+/// Default timeout of a single API request; downloads have none.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[cfg(feature = "cache")]
 const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 10);
 #[cfg(feature = "cache")]
 const DEFAULT_CACHE_CAPACITY: u64 = 10_000;
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub(crate) struct ApiCore {
-    client: Client,
+    transport: Arc<dyn Transport>,
+    base_urls: BaseUrls,
+    timeout: Duration,
+    drift: Option<DriftReport>,
     #[cfg(feature = "cache")]
     cache: Option<Cache<String, Vec<u8>>>,
 }
 
+impl Default for ApiCore {
+    fn default() -> Self {
+        Self {
+            transport: Arc::new(ReqwestTransport::default()),
+            base_urls: BaseUrls::default(),
+            timeout: DEFAULT_TIMEOUT,
+            drift: None,
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+}
+
 impl ApiCore {
     #[cfg(feature = "cache")]
     fn default_cache() -> Cache<String, Vec<u8>> {
@@ -29,37 +50,44 @@ impl ApiCore {
     }
 
     fn new() -> Self {
-        let client = Client::builder()
-            .user_agent(concat!(
-                env!("CARGO_PKG_NAME"),
-                "/",
-                env!("CARGO_PKG_VERSION")
-            ))
-            .default_headers({
-                let mut h = header::HeaderMap::new();
-                h.insert(
-                    header::ACCEPT,
-                    header::HeaderValue::from_static("application/json"),
-                );
-                h
-            })
-            .build()
-            .unwrap_or_else(|_| Client::new());
-        Self {
-            client,
-            #[cfg(feature = "cache")]
-            cache: Some(Self::default_cache()),
-        }
+        Self::new_with_transport(ReqwestTransport::with_defaults())
     }
 
     fn new_with_client(client: Client) -> Self {
+        Self::new_with_transport(ReqwestTransport::new(client))
+    }
+
+    fn new_with_transport(transport: impl Transport + 'static) -> Self {
         Self {
-            client,
+            transport: Arc::new(transport),
+            base_urls: BaseUrls::default(),
+            timeout: DEFAULT_TIMEOUT,
+            drift: None,
             #[cfg(feature = "cache")]
             cache: Some(Self::default_cache()),
         }
     }
 
+    fn set_base_urls(&mut self, base_urls: BaseUrls) {
+        self.base_urls = base_urls;
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    fn set_drift_report(&mut self, report: Option<DriftReport>) {
+        self.drift = report;
+    }
+
+    /// Parses a response body, checking it for schema drift when a report is set.
+    fn parse<T>(&self, url: &str, body: &[u8]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        parse_response(url, body, self.drift.as_ref())
+    }
+
     #[cfg(feature = "cache")]
     fn set_cache(&mut self, capacity: u64, time_to_live: Duration) {
         self.cache = Some(
@@ -72,27 +100,36 @@ impl ApiCore {
 
     /// Sends an HTTP GET request to the specified URL and returns the raw response body.
     async fn send_request(&self, url: &str) -> Result<Vec<u8>> {
-        let response = self
-            .client
-            .get(url)
+        let request = HttpRequest {
+            url: self.base_urls.rewrite(url),
+            headers: default_headers(),
             // Avoid hanging forever on large responses while still being generous.
-            .timeout(Duration::from_secs(30))
-            .send()
-            .await?;
-
-        let status = response.status();
-        let body = response.bytes().await?.to_vec();
-
-        if body.is_empty() {
-            return Err(Error::EmptyBody);
-        }
+            timeout: Some(self.timeout),
+        };
+        let response = self
+            .transport
+            .send(request)
+            .await
+            .map_err(|e| e.with_url(url))?;
+        check_response(url, response)
+    }
 
-        if !status.is_success() {
-            let msg = String::from_utf8_lossy(&body).chars().take(2048).collect();
-            return Err(Error::HttpStatus { status, body: msg });
+    /// Starts a GET request for a large download, leaving the body unread.
+    #[cfg(feature = "download")]
+    pub(crate) async fn open_download(&self, url: &str) -> Result<Download> {
+        let request = HttpRequest::new(self.base_urls.rewrite(url));
+        let response = self
+            .transport
+            .download(request)
+            .await
+            .map_err(|e| e.with_url(url))?;
+        if !response.status.is_success() {
+            let status = response.status;
+            let headers = response.headers.clone();
+            let body = response.bytes().await.map_err(|e| e.with_url(url))?;
+            return Err(status_error(url, status, &headers, &body));
         }
-
-        Ok(body)
+        Ok(response)
     }
 
     pub async fn _generator<T>(&self, url: &str) -> Result<T>
@@ -120,11 +157,11 @@ impl ApiCore {
         match &self.cache {
             Some(cache) => {
                 if let Some(value) = cache.get(&cache_key).await {
-                    self.parse_response::<T>(value.as_slice())
+                    self.parse::<T>(url, value.as_slice())
                 } else {
                     let body = self.send_request(url).await?;
                     cache.insert(cache_key, body.clone()).await;
-                    self.parse_response::<T>(body.as_slice())
+                    self.parse::<T>(url, body.as_slice())
                 }
             }
             None => self._generator_no_cache(url).await,
@@ -136,50 +173,122 @@ impl ApiCore {
         T: DeserializeOwned,
     {
         let body = self.send_request(url).await?;
-        self.parse_response::<T>(body.as_slice())
+        self.parse::<T>(url, body.as_slice())
+    }
+}
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+fn default_headers() -> header::HeaderMap {
+    let mut h = header::HeaderMap::new();
+    h.insert(
+        header::ACCEPT,
+        header::HeaderValue::from_static("application/json"),
+    );
+    h
+}
+
+/// Turns a non-success status or an empty body into an error.
+fn check_response(url: &str, response: HttpResponse) -> Result<Vec<u8>> {
+    if !response.status.is_success() {
+        return Err(status_error(
+            url,
+            response.status,
+            &response.headers,
+            &response.body,
+        ));
     }
 
-    fn parse_response<T>(&self, body: &[u8]) -> Result<T>
-    where
-        T: DeserializeOwned,
+    if response.body.is_empty() {
+        return Err(Error::EmptyBody {
+            endpoint: Endpoint::from_url(url),
+            url: url.to_string(),
+        });
+    }
+
+    Ok(response.body)
+}
+
+fn status_error(
+    url: &str,
+    status: reqwest::StatusCode,
+    headers: &header::HeaderMap,
+    body: &[u8],
+) -> Error {
+    let endpoint = Endpoint::from_url(url);
+    let url = url.to_string();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = headers
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+            .map(Duration::from_secs);
+        return Error::RateLimited {
+            endpoint,
+            url,
+            retry_after,
+        };
+    }
+
+    let body = String::from_utf8_lossy(body).chars().take(2048).collect();
+    Error::HttpStatus {
+        endpoint,
+        url,
+        status,
+        body,
+    }
+}
+
+fn parse_response<T>(url: &str, body: &[u8], drift: Option<&DriftReport>) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    // ddnet "not found" convention: empty JSON object.
+    #[cfg(feature = "ddnet")]
     {
-        // ddnet "not found" convention: empty JSON object.
-        #[cfg(feature = "ddnet")]
-        {
-            let trimmed = trim_ascii(body);
-            if trimmed == b"{}" {
-                return Err(Error::NotFound);
-            }
+        let trimmed = trim_ascii(body);
+        if trimmed == b"{}" {
+            return Err(Error::NotFound {
+                endpoint: Endpoint::from_url(url),
+                url: url.to_string(),
+            });
         }
+    }
 
-        // ddstats sometimes returns HTTP 200 with { "error": "..." }.
-        #[cfg(feature = "ddstats")]
-        {
-            #[derive(serde::Deserialize)]
-            #[serde(untagged)]
-            enum MaybeError<T> {
-                Err { error: String },
-                Ok(T),
-            }
+    // ddstats sometimes returns HTTP 200 with { "error": "..." }.
+    // Checked on its own so the payload parse below can track the JSON path.
+    #[cfg(feature = "ddstats")]
+    {
+        #[derive(serde::Deserialize)]
+        struct Envelope {
+            error: String,
+        }
 
-            // Single-pass parse: either error envelope or expected payload.
-            match serde_json::from_slice::<MaybeError<T>>(body)? {
-                MaybeError::Err { error } => {
-                    if error.eq_ignore_ascii_case("player not found") {
-                        Err(Error::NotFound)
-                    } else {
-                        Err(Error::RemoteMessage(error))
-                    }
+        if let Ok(Envelope { error }) = serde_json::from_slice::<Envelope>(body) {
+            let endpoint = Endpoint::from_url(url);
+            let url = url.to_string();
+            return Err(if error.eq_ignore_ascii_case("player not found") {
+                Error::NotFound { endpoint, url }
+            } else {
+                Error::RemoteMessage {
+                    endpoint,
+                    url,
+                    message: error,
                 }
-                MaybeError::Ok(v) => Ok(v),
-            }
+            });
         }
+    }
 
-        #[cfg(not(feature = "ddstats"))]
-        {
-            Ok(serde_json::from_slice(body)?)
-        }
+    if let Some(report) = drift {
+        return report.deserialize(url, body);
     }
+
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|e| Error::from(e).with_url(url))?;
+    deserializer
+        .end()
+        .map_err(|e| Error::from(e).with_url(url))?;
+    Ok(value)
 }
 
 fn trim_ascii(mut s: &[u8]) -> &[u8] {
@@ -256,6 +365,41 @@ impl DDApi {
         }
     }
 
+    /// Creates a new DDApi instance that sends its requests through `transport`
+    ///
+    /// See [`Transport`] for an example.
+    pub fn new_with_transport(transport: impl Transport + 'static) -> Self {
+        DDApi {
+            core: ApiCore::new_with_transport(transport),
+        }
+    }
+
+    /// Sends requests for the configured hosts to other base URLs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ddapi_rs::prelude::*;
+    ///
+    /// let mut api = DDApi::new();
+    /// api.set_base_urls(BaseUrls::new().with("ddnet.org", "http://localhost:8080"));
+    /// ```
+    pub fn set_base_urls(&mut self, base_urls: BaseUrls) {
+        self.core.set_base_urls(base_urls);
+    }
+
+    /// Sets the timeout of a single request, [`DEFAULT_TIMEOUT`] by default
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.core.set_timeout(timeout);
+    }
+
+    /// Checks every parsed response for schema drift, see [`DriftReport`]
+    ///
+    /// Pass `None` to stop checking.
+    pub fn set_drift_report(&mut self, report: impl Into<Option<DriftReport>>) {
+        self.core.set_drift_report(report.into());
+    }
+
     /// Configures caching for API responses
     ///
     /// When the `cache` feature is enabled, this method allows you to set up
@@ -353,6 +497,24 @@ impl DDnetClient {
         }
     }
 
+    pub fn new_with_transport(transport: impl Transport + 'static) -> Self {
+        Self {
+            core: ApiCore::new_with_transport(transport),
+        }
+    }
+
+    pub fn set_base_urls(&mut self, base_urls: BaseUrls) {
+        self.core.set_base_urls(base_urls);
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.core.set_timeout(timeout);
+    }
+
+    pub fn set_drift_report(&mut self, report: impl Into<Option<DriftReport>>) {
+        self.core.set_drift_report(report.into());
+    }
+
     #[cfg(feature = "cache")]
     pub fn set_cache(&mut self, capacity: u64, time_to_live: Duration) {
         self.core.set_cache(capacity, time_to_live);
@@ -383,14 +545,56 @@ impl DDstatsClient {
         }
     }
 
+    pub fn new_with_transport(transport: impl Transport + 'static) -> Self {
+        Self {
+            core: ApiCore::new_with_transport(transport),
+        }
+    }
+
+    pub fn set_base_urls(&mut self, base_urls: BaseUrls) {
+        self.core.set_base_urls(base_urls);
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.core.set_timeout(timeout);
+    }
+
+    pub fn set_drift_report(&mut self, report: impl Into<Option<DriftReport>>) {
+        self.core.set_drift_report(report.into());
+    }
+
     #[cfg(feature = "cache")]
     pub fn set_cache(&mut self, capacity: u64, time_to_live: Duration) {
         self.core.set_cache(capacity, time_to_live);
     }
 }
 
+mod drift;
+mod transport;
+
 #[cfg(feature = "ddnet")]
 pub mod ddnet;
 
+#[cfg(feature = "ddnet")]
+pub mod records;
+
+#[cfg(feature = "download")]
+pub mod download;
+
 #[cfg(feature = "ddstats")]
 pub mod ddstats;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "stream")]
+pub mod stream;
+
+#[cfg(feature = "feeds")]
+pub mod feed;
+
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,134 @@
+use crate::api::ddnet::DDnetApi;
+use crate::error::Result;
+use crate::scheme::ddnet::prelude::{DDMap, FinishClass, LatestFinishes, MapRecords, PreviousBest};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long fetched map ranks and player times are trusted by default.
+pub const DEFAULT_RECORDS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Classifies incoming finishes as records, top-10 ranks or personal bests.
+///
+/// Map ranks are fetched once per map and then updated with every classified
+/// finish, so they are only fetched again after `max_age`. Player data is
+/// only fetched when the ranks cannot tell the player's previous best: they
+/// are not listed, or the ranks were fetched after this finish and list it.
+/// Both bypass the client's response cache, so `max_age` and
+/// [`FinishClassifier::invalidate`] always lead to current data.
+///
+/// # Examples
+/// ```rust,ignore
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::ddnet::FinishClassifier;
+///
+/// let api = DDApi::new();
+/// let mut classifier = FinishClassifier::new(api.clone());
+/// for finish in api.latest_finish().await?.iter().rev() {
+///     let class = classifier.classify(finish).await?;
+///     if class.world_record {
+///         println!("new record on {} by {}: {}", finish.map, finish.name, finish.race_time());
+///     } else if class.top10 {
+///         println!("{} is now rank {:?} on {}", finish.name, class.rank, finish.map);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FinishClassifier<A> {
+    api: A,
+    max_age: Duration,
+    maps: HashMap<String, (Instant, MapRecords)>,
+    players: HashMap<String, (Instant, HashMap<String, DDMap>)>,
+}
+
+impl<A: DDnetApi + Send + Sync> FinishClassifier<A> {
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            max_age: DEFAULT_RECORDS_MAX_AGE,
+            maps: HashMap::new(),
+            players: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// The cached ranks of `map`.
+    pub fn records(&self, map: &str) -> Option<&MapRecords> {
+        self.maps.get(map).map(|(_, records)| records)
+    }
+
+    /// Forgets the cached ranks of `map`; they are fetched again on its next finish.
+    pub fn invalidate(&mut self, map: &str) {
+        self.maps.remove(map);
+    }
+
+    /// Classifies `finish` and adds it to the cached data.
+    ///
+    /// Finishes should be passed oldest first, so that each one is compared
+    /// against the ones before it.
+    pub async fn classify(&mut self, finish: &LatestFinishes) -> Result<FinishClass> {
+        let fresh = |fetched: &Instant| fetched.elapsed() < self.max_age;
+
+        let (_, records) = match self.maps.entry(finish.map.clone()) {
+            Entry::Occupied(entry) if fresh(&entry.get().0) => entry.into_mut(),
+            entry => {
+                let map = self.api.map_no_cache(&finish.map).await?;
+                entry
+                    .insert_entry((Instant::now(), MapRecords::new(&map)))
+                    .into_mut()
+            }
+        };
+
+        let listed = records.rank_of(&finish.name).is_some();
+        let included = records.includes(finish);
+        let previous_best = if (listed || records.complete()) && !included {
+            PreviousBest::NotFinished
+        } else {
+            if !self
+                .players
+                .get(&finish.name)
+                .is_some_and(|(at, _)| fresh(at))
+            {
+                let player = self.api.player_no_cache(&finish.name).await?;
+                let maps = player
+                    .types
+                    .maps()
+                    .map(|(_, name, map)| (name.to_string(), map.clone()))
+                    .collect();
+                self.players
+                    .insert(finish.name.clone(), (Instant::now(), maps));
+            }
+            match self
+                .players
+                .get(&finish.name)
+                .and_then(|(_, maps)| maps.get(&finish.map))
+            {
+                // Fetched after this finish, which is the player's best.
+                Some(map) if map.time == Some(finish.time) => {
+                    if map.finishes > 1 {
+                        PreviousBest::Slower
+                    } else {
+                        PreviousBest::NotFinished
+                    }
+                }
+                Some(map) => map.time.into(),
+                None => PreviousBest::NotFinished,
+            }
+        };
+
+        let class = records.classify(finish, previous_best);
+        records.apply(finish);
+        if class.personal_best {
+            if let Some((_, maps)) = self.players.get_mut(&finish.name) {
+                let map = maps.entry(finish.map.clone()).or_default();
+                map.time = Some(finish.time);
+                map.finishes += 1;
+            }
+        }
+        Ok(class)
+    }
+}
@@ -0,0 +1,382 @@
+use crate::api::ddnet::DDnetApi;
+use crate::error::Result;
+use crate::scheme::ddnet::prelude::LatestFinishes;
+use crate::scheme::{deserialize_datetime_timestamp, serialize_datetime_timestamp};
+use crate::util::prelude::RaceTime;
+use chrono::{DateTime, NaiveDateTime, TimeDelta};
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use std::time::Duration;
+
+/// Default time between two polls of the latest finishes.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Default number of pages fetched per poll when catching up after a burst.
+pub const DEFAULT_MAX_PAGES: usize = 10;
+/// How far behind the newest finish a [`FinishCursor`] still accepts finishes
+/// the API lists late, e.g. after a server synced its ranks with a delay.
+pub const CURSOR_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// Most finish keys a [`FinishCursor`] remembers; beyond that it forgets the oldest.
+pub const MAX_CURSOR_KEYS: usize = 4096;
+
+/// What makes a finish unique: `(timestamp, map, name, time, server)`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FinishKey {
+    #[serde(
+        serialize_with = "serialize_datetime_timestamp",
+        deserialize_with = "deserialize_datetime_timestamp"
+    )]
+    pub timestamp: NaiveDateTime,
+    pub map: String,
+    pub name: String,
+    /// Rounded to whole milliseconds, so the key can be hashed and ordered.
+    pub time: RaceTime,
+    pub server: String,
+}
+
+impl From<&LatestFinishes> for FinishKey {
+    fn from(finish: &LatestFinishes) -> Self {
+        Self {
+            timestamp: finish.timestamp,
+            map: finish.map.clone(),
+            name: finish.name.clone(),
+            time: finish.race_time(),
+            server: finish.server.clone(),
+        }
+    }
+}
+
+/// Position in the finish feed: the finishes already emitted within
+/// [`CURSOR_WINDOW`] of the newest one, at most [`MAX_CURSOR_KEYS`] of them.
+/// Anything older counts as emitted, so a finish that shows up late is still
+/// emitted once as long as it falls into the window.
+///
+/// Serialize it to resume a [`FinishFeed`] after a restart without emitting
+/// anything twice. Keep it up to date with [`FinishCursor::advance`] for
+/// every finish the stream yields.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::LatestFinishes;
+/// use ddapi_rs::prelude::stream::FinishCursor;
+///
+/// let finish = |timestamp: &str, name: &str| LatestFinishes {
+///     timestamp: timestamp.parse().unwrap(),
+///     map: "Multeasymap".to_string(),
+///     name: name.to_string(),
+///     time: 100.0,
+///     server: "GER".to_string(),
+/// };
+///
+/// // Windows are newest first, like the API returns them.
+/// let first = vec![finish("2024-05-06T20:00:01", "b"), finish("2024-05-06T20:00:00", "a")];
+/// let mut cursor = FinishCursor::default();
+/// for new in cursor.filter_new(&first) {
+///     cursor.advance(&new);
+/// }
+///
+/// let second = vec![
+///     finish("2024-05-06T20:00:02", "c"),
+///     finish("2024-05-06T20:00:01", "d"),
+///     finish("2024-05-06T20:00:01", "b"),
+/// ];
+/// assert!(cursor.overlaps(&second));
+/// let names: Vec<String> = cursor.filter_new(&second).into_iter().map(|f| f.name).collect();
+/// assert_eq!(names, ["d", "c"]);
+/// cursor.advance(&second[0]);
+/// cursor.advance(&second[1]);
+///
+/// // A finish listed late, behind newer ones, is still new; one from hours ago is not.
+/// let late = finish("2024-05-06T19:59:00", "e");
+/// assert!(cursor.is_new(&late));
+/// cursor.advance(&late);
+/// assert!(!cursor.is_new(&late));
+/// assert!(!cursor.is_new(&finish("2024-05-06T12:00:00", "f")));
+///
+/// let saved = serde_json::to_string(&cursor).unwrap();
+/// let restored: FinishCursor = serde_json::from_str(&saved).unwrap();
+/// assert_eq!(restored, cursor);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinishCursor {
+    seen: BTreeSet<FinishKey>,
+    /// Unix timestamp up to which everything counts as emitted, set once
+    /// keys had to be forgotten to stay within [`MAX_CURSOR_KEYS`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    floor: Option<i64>,
+}
+
+impl FinishCursor {
+    /// A cursor positioned after every finish in `window`, so only later ones are emitted.
+    pub fn after(window: &[LatestFinishes]) -> Self {
+        let mut cursor = Self::default();
+        for finish in window {
+            cursor.advance(finish);
+        }
+        cursor
+    }
+
+    /// Timestamp of the newest finish emitted, `None` for a fresh cursor.
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        self.seen.last().map(|key| key.timestamp).max(self.floor())
+    }
+
+    /// Records `finish` as emitted.
+    pub fn advance(&mut self, finish: &LatestFinishes) {
+        if !self.is_new(finish) {
+            return;
+        }
+        self.seen.insert(finish.into());
+
+        let Some(cutoff) = self.cutoff() else {
+            return;
+        };
+        while self.seen.first().is_some_and(|key| key.timestamp < cutoff) {
+            self.seen.pop_first();
+        }
+        while self.seen.len() > MAX_CURSOR_KEYS {
+            if let Some(oldest) = self.seen.pop_first() {
+                self.floor = Some(oldest.timestamp.and_utc().timestamp());
+            }
+        }
+        if let Some(floor) = self.floor() {
+            // Keys at the floor are covered by it.
+            while self.seen.first().is_some_and(|key| key.timestamp <= floor) {
+                self.seen.pop_first();
+            }
+            if floor < cutoff {
+                self.floor = None;
+            }
+        }
+    }
+
+    pub fn is_new(&self, finish: &LatestFinishes) -> bool {
+        let Some(cutoff) = self.cutoff() else {
+            return true;
+        };
+        finish.timestamp >= cutoff
+            && self.floor().is_none_or(|floor| finish.timestamp > floor)
+            && !self.seen.contains(&FinishKey::from(finish))
+    }
+
+    /// Oldest timestamp still inside [`CURSOR_WINDOW`].
+    fn cutoff(&self) -> Option<NaiveDateTime> {
+        let window = TimeDelta::seconds(CURSOR_WINDOW.as_secs() as i64);
+        Some(self.timestamp()? - window)
+    }
+
+    fn floor(&self) -> Option<NaiveDateTime> {
+        self.floor
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .map(|date| date.naive_utc())
+    }
+
+    /// Whether `window` reaches back to the cursor, i.e. nothing can be
+    /// missing between the cursor and the window. A fresh cursor overlaps everything.
+    pub fn overlaps(&self, window: &[LatestFinishes]) -> bool {
+        match self.timestamp() {
+            None => true,
+            Some(timestamp) => window.iter().any(|finish| finish.timestamp <= timestamp),
+        }
+    }
+
+    /// The finishes of `window` not emitted yet, oldest first and without duplicates.
+    pub fn filter_new(&self, window: &[LatestFinishes]) -> Vec<LatestFinishes> {
+        let mut keys: BTreeSet<FinishKey> = BTreeSet::new();
+        let mut new: Vec<LatestFinishes> = window
+            .iter()
+            .rev()
+            .filter(|finish| self.is_new(finish) && keys.insert(FinishKey::from(*finish)))
+            .cloned()
+            .collect();
+        // Stable, so finishes sharing a timestamp keep the API's order.
+        new.sort_by_key(|finish| finish.timestamp);
+        new
+    }
+}
+
+/// An item of a [`FinishFeed`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedEvent {
+    Finish(LatestFinishes),
+    /// Paging stopped before reaching the cursor, so finishes between `since`
+    /// and `until` may have been missed.
+    ///
+    /// Paging treats `?latest=N` as the number of newest finishes to skip,
+    /// which is unverified against ddnet.org. Where the API ignores it and
+    /// repeats the first page, paging stops there and catching up after a
+    /// burst always ends in a gap.
+    Gap {
+        since: NaiveDateTime,
+        until: NaiveDateTime,
+    },
+}
+
+/// Polls the DDNet latest finishes and emits each finish exactly once.
+///
+/// Overlapping windows are deduplicated with a [`FinishCursor`]. When more
+/// finishes happened between two polls than one response holds, older pages
+/// are fetched (passing the number of entries already seen as `latest`, see
+/// [`FeedEvent::Gap`] for that assumption) until they reach the cursor again,
+/// up to [`FinishFeed::with_max_pages`] pages. If that is not enough, a
+/// [`FeedEvent::Gap`] precedes the finishes.
+///
+/// # Examples
+/// ```rust,ignore
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::stream::{FeedEvent, FinishCursor, FinishFeed, StreamExt};
+/// use std::time::Duration;
+///
+/// let mut cursor: FinishCursor = load_cursor().unwrap_or_default();
+/// let feed = FinishFeed::new(DDApi::new())
+///     .with_interval(Duration::from_secs(15))
+///     .with_cursor(cursor.clone());
+///
+/// let mut events = std::pin::pin!(feed.into_stream());
+/// while let Some(event) = events.next().await {
+///     match event? {
+///         FeedEvent::Finish(finish) => {
+///             println!("{} finished {} in {}", finish.name, finish.map, finish.race_time());
+///             cursor.advance(&finish);
+///             save_cursor(&cursor);
+///         }
+///         FeedEvent::Gap { since, until } => eprintln!("finishes between {since} and {until} missed"),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FinishFeed<A> {
+    api: A,
+    interval: Duration,
+    max_pages: usize,
+    cursor: FinishCursor,
+}
+
+impl<A: DDnetApi + Send + Sync> FinishFeed<A> {
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            interval: DEFAULT_POLL_INTERVAL,
+            max_pages: DEFAULT_MAX_PAGES,
+            cursor: FinishCursor::default(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Limits how far back one poll pages when catching up. At least one page is always fetched.
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Resumes from a saved cursor. Without one, the first poll emits the whole current window.
+    pub fn with_cursor(mut self, cursor: FinishCursor) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    pub fn cursor(&self) -> &FinishCursor {
+        &self.cursor
+    }
+
+    /// Fetches once and returns the new finishes, oldest first, advancing the cursor.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::*;
+    /// use ddapi_rs::prelude::ddnet::LatestFinishes;
+    /// use ddapi_rs::prelude::stream::{FeedEvent, FinishCursor, FinishFeed};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// // Answers every page with the same finish, as if `latest` were ignored.
+    /// struct Burst;
+    ///
+    /// impl Transport for Burst {
+    ///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+    ///         REQUESTS.fetch_add(1, Ordering::SeqCst);
+    ///         Box::pin(async {
+    ///             Ok(HttpResponse::ok(
+    ///                 r#"[{"timestamp": 1715025660, "map": "Tutorial", "name": "b", "time": 80.0, "server": "GER"}]"#,
+    ///             ))
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let seen = LatestFinishes {
+    ///     timestamp: "2024-05-06T20:00:00".parse().unwrap(),
+    ///     name: "a".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let mut feed = FinishFeed::new(DDnetClient::new_with_transport(Burst))
+    ///     .with_max_pages(5)
+    ///     .with_cursor(FinishCursor::after(&[seen.clone()]));
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let events = feed.poll().await.unwrap();
+    /// assert_eq!(events.len(), 2);
+    /// assert_eq!(
+    ///     events[0],
+    ///     FeedEvent::Gap { since: seen.timestamp, until: "2024-05-06T20:01:00".parse().unwrap() }
+    /// );
+    /// assert!(matches!(&events[1], FeedEvent::Finish(finish) if finish.name == "b"));
+    /// // The repeated second page stopped paging.
+    /// assert_eq!(REQUESTS.load(Ordering::SeqCst), 2);
+    /// # });
+    /// ```
+    pub async fn poll(&mut self) -> Result<Vec<FeedEvent>> {
+        let mut window: Vec<LatestFinishes> = Vec::new();
+        let mut reached = false;
+        for _ in 0..self.max_pages {
+            let page = self.api.latest_finish_with_latest(window.len()).await?;
+            reached = page.is_empty() || self.cursor.overlaps(&page);
+            // The same first finish again means the offset was ignored.
+            let repeated = !window.is_empty() && window.first() == page.first();
+            if !repeated {
+                window.extend(page);
+            }
+            if reached || repeated {
+                break;
+            }
+        }
+
+        let mut events = Vec::new();
+        let oldest = window.iter().map(|finish| finish.timestamp).min();
+        if let (false, Some(since), Some(until)) = (reached, self.cursor.timestamp(), oldest) {
+            events.push(FeedEvent::Gap { since, until });
+        }
+        let new = self.cursor.filter_new(&window);
+        for finish in &new {
+            self.cursor.advance(finish);
+        }
+        events.extend(new.into_iter().map(FeedEvent::Finish));
+        Ok(events)
+    }
+
+    /// Turns the feed into an endless stream of new finishes and gaps.
+    ///
+    /// A failed poll yields its error and is retried after the next interval.
+    pub fn into_stream(self) -> impl Stream<Item = Result<FeedEvent>> + Send {
+        let state = (self, VecDeque::new(), true);
+        stream::unfold(state, |(mut feed, mut pending, mut first)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((Ok(event), (feed, pending, first)));
+                }
+                if !first {
+                    tokio::time::sleep(feed.interval).await;
+                }
+                first = false;
+                match feed.poll().await {
+                    Ok(new) => pending.extend(new),
+                    Err(e) => return Some((Err(e), (feed, pending, first))),
+                }
+            }
+        })
+    }
+}
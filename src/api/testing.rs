@@ -0,0 +1,249 @@
+use crate::api::BaseUrls;
+use crate::error::Result;
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Largest request head the server reads before giving up on a connection.
+const MAX_REQUEST_HEAD: usize = 64 * 1024;
+
+/// A canned answer of the [`MockServer`], with optional failure injection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Wait this long before answering, e.g. to trigger client timeouts.
+    pub delay: Option<Duration>,
+    /// Send only this many bytes of the body (with a matching `Content-Length`).
+    pub truncate: Option<usize>,
+    /// Close the connection without answering.
+    pub disconnect: bool,
+}
+
+impl MockResponse {
+    /// `200 OK` with a JSON body.
+    pub fn json(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: body.into(),
+            delay: None,
+            truncate: None,
+            disconnect: false,
+        }
+    }
+
+    /// `200 OK` with `value` serialized as JSON.
+    pub fn from_value<T: Serialize>(value: &T) -> Result<Self> {
+        Ok(Self::json(serde_json::to_vec(value)?))
+    }
+
+    /// DDNet's "not found": `200 OK` with an empty JSON object.
+    pub fn not_found() -> Self {
+        Self::json("{}")
+    }
+
+    /// DDStats' error envelope: `200 OK` with `{"error": message}`.
+    pub fn ddstats_error(message: &str) -> Self {
+        Self::json(serde_json::json!({ "error": message }).to_string())
+    }
+
+    /// Any status with a plain body.
+    pub fn status(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            ..Self::json(body)
+        }
+    }
+
+    pub fn server_error() -> Self {
+        Self::status(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Cuts the body in half, leaving syntactically broken JSON.
+    pub fn truncated(mut self) -> Self {
+        self.truncate = Some(self.body.len() / 2);
+        self
+    }
+
+    pub fn disconnected(mut self) -> Self {
+        self.disconnect = true;
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let len = self
+            .truncate
+            .map_or(self.body.len(), |n| n.min(self.body.len()));
+        let body = &self.body[..len];
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\ncontent-length: {}\r\nconnection: close\r\n",
+            self.status.as_u16(),
+            self.status.canonical_reason().unwrap_or(""),
+            body.len()
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(body);
+        bytes
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    routes: HashMap<String, MockResponse>,
+    requests: Vec<String>,
+}
+
+/// A local HTTP server standing in for ddnet.org, masterN.ddnet.org,
+/// skins.ddnet.org, maps.ddnet.org and ddstats.tw.
+///
+/// Every host is served under its own path prefix (see [`BaseUrls::all`]);
+/// point a client at it with [`MockServer::base_urls`] and register
+/// responses by the URL the crate would request. Requests without a
+/// response get a `404`. The server stops when it is dropped.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::ddnet::Player;
+/// use ddapi_rs::prelude::testing::{MockResponse, MockServer};
+/// use std::time::Duration;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let server = MockServer::start().await.unwrap();
+/// server.mock(&Player::api("nameless tee"), MockResponse::not_found());
+/// server.mock(&Player::api("slow"), MockResponse::not_found().with_delay(Duration::from_secs(5)));
+/// server.mock(&Player::api("broken"), MockResponse::json(r#"{"player": "broken"}"#).truncated());
+///
+/// let mut api = DDnetClient::new();
+/// api.set_base_urls(server.base_urls());
+/// api.set_timeout(Duration::from_millis(200));
+///
+/// assert!(matches!(api.player("nameless tee").await, Err(Error::NotFound { .. })));
+/// assert!(matches!(api.player("slow").await, Err(Error::Timeout { .. })));
+/// assert!(matches!(api.player("broken").await, Err(Error::Json { .. })));
+/// assert!(matches!(api.status().await, Err(Error::HttpStatus { .. })));
+/// assert_eq!(server.requests().len(), 4);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Binds to a free port on localhost and starts serving on the current tokio runtime.
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::default()));
+        let task = tokio::spawn(serve(listener, state.clone()));
+        Ok(Self { addr, state, task })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Base URLs sending every API host to this server.
+    pub fn base_urls(&self) -> BaseUrls {
+        BaseUrls::all(&self.url())
+    }
+
+    /// Answers requests for `url` (as built by the crate, e.g. `Player::api(..)`) with `response`.
+    pub fn mock(&self, url: &str, response: MockResponse) {
+        let path = self.path(url);
+        self.lock().routes.insert(path, response);
+    }
+
+    pub fn remove(&self, url: &str) {
+        let path = self.path(url);
+        self.lock().routes.remove(&path);
+    }
+
+    /// Paths of all requests received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.lock().requests.clone()
+    }
+
+    fn path(&self, url: &str) -> String {
+        let url = self.base_urls().rewrite(url);
+        url.strip_prefix(&self.url()).unwrap_or(&url).to_string()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<State>>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(handle(stream, state.clone()));
+    }
+}
+
+async fn handle(mut stream: TcpStream, state: Arc<Mutex<State>>) {
+    let Some(path) = read_path(&mut stream).await else {
+        return;
+    };
+    let response = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.requests.push(path.clone());
+        state.routes.get(&path).cloned().unwrap_or_else(|| {
+            MockResponse::status(StatusCode::NOT_FOUND, format!("no mock for {path}"))
+        })
+    };
+
+    if let Some(delay) = response.delay {
+        tokio::time::sleep(delay).await;
+    }
+    if !response.disconnect {
+        let _ = stream.write_all(&response.to_bytes()).await;
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// Reads the request head and returns the request target.
+async fn read_path(stream: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.ok()?;
+        if n == 0 || head.len() > MAX_REQUEST_HEAD {
+            return None;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    let line = String::from_utf8_lossy(&head);
+    line.lines().next()?.split(' ').nth(1).map(str::to_string)
+}
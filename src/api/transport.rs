@@ -0,0 +1,274 @@
+use crate::api::{default_headers, USER_AGENT};
+use crate::error::Result;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Boxed future returned by [`Transport`] methods.
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// A GET request the crate wants to make.
+#[derive(Debug, Clone, Default)]
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: HeaderMap,
+    /// `None` for downloads, which may take arbitrarily long.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// What a [`Transport`] hands back: status, headers and the whole body.
+///
+/// Non-success statuses are returned as responses, not errors; the crate
+/// turns them into [`Error::HttpStatus`](crate::error::Error::HttpStatus) itself.
+#[derive(Debug, Clone, Default)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// A `200 OK` response with `body` and no headers.
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// The HTTP stack behind [`DDApi`](crate::api::DDApi), [`DDnetClient`](crate::api::DDnetClient)
+/// and [`DDstatsClient`](crate::api::DDstatsClient).
+///
+/// [`ReqwestTransport`] is used unless a client is built with `new_with_transport`,
+/// which allows instrumented HTTP stacks or in-memory fakes for tests.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+/// use ddapi_rs::prelude::ddnet::DDnetApi;
+///
+/// struct Fake;
+///
+/// impl Transport for Fake {
+///     fn send(&self, request: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async move {
+///             assert!(request.url.contains("latest="));
+///             Ok(HttpResponse::ok(
+///                 r#"[{"timestamp": 1715025600, "map": "Tutorial", "name": "nameless tee", "time": 30.0, "server": "GER"}]"#,
+///             ))
+///         })
+///     }
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let api = DDnetClient::new_with_transport(Fake);
+/// let finishes = api.latest_finish().await.unwrap();
+/// assert_eq!(finishes[0].name, "nameless tee");
+/// # });
+/// ```
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_, HttpResponse>;
+
+    /// Starts a large download. The default buffers the whole body with [`Transport::send`].
+    #[cfg(feature = "download")]
+    fn download(&self, request: HttpRequest) -> TransportFuture<'_, Download> {
+        Box::pin(async move { Ok(Download::from(self.send(request).await?)) })
+    }
+}
+
+/// The default transport, a `reqwest::Client`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// A client with the crate's user agent and default headers, as used by `DDApi::new`.
+    pub fn with_defaults() -> Self {
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .default_headers(default_headers())
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self::new(client)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    fn get(&self, request: HttpRequest) -> reqwest::RequestBuilder {
+        let builder = self.client.get(&request.url).headers(request.headers);
+        match request.timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+        Box::pin(async move {
+            let response = self.get(request).send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+
+    #[cfg(feature = "download")]
+    fn download(&self, request: HttpRequest) -> TransportFuture<'_, Download> {
+        Box::pin(async move {
+            let response = self.get(request).send().await?;
+            Ok(Download {
+                status: response.status(),
+                headers: response.headers().clone(),
+                content_length: response.content_length(),
+                body: DownloadBody::Reqwest(response),
+            })
+        })
+    }
+}
+
+/// A response whose body is read in chunks.
+#[cfg(feature = "download")]
+pub struct Download {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub content_length: Option<u64>,
+    body: DownloadBody,
+}
+
+#[cfg(feature = "download")]
+enum DownloadBody {
+    Buffered(Option<Vec<u8>>),
+    Reqwest(reqwest::Response),
+}
+
+#[cfg(feature = "download")]
+impl Download {
+    /// The next chunk of the body, `None` at the end.
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        match &mut self.body {
+            DownloadBody::Buffered(body) => Ok(body.take().filter(|body| !body.is_empty())),
+            DownloadBody::Reqwest(response) => Ok(response.chunk().await?.map(|c| c.to_vec())),
+        }
+    }
+
+    /// Reads the rest of the body into memory.
+    pub async fn bytes(mut self) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(feature = "download")]
+impl From<HttpResponse> for Download {
+    fn from(response: HttpResponse) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers,
+            content_length: Some(response.body.len() as u64),
+            body: DownloadBody::Buffered(Some(response.body)),
+        }
+    }
+}
+
+/// Hosts the crate sends requests to.
+pub const API_HOSTS: [&str; 8] = [
+    "ddnet.org",
+    "master1.ddnet.org",
+    "master2.ddnet.org",
+    "master3.ddnet.org",
+    "master4.ddnet.org",
+    "skins.ddnet.org",
+    "maps.ddnet.org",
+    "ddstats.tw",
+];
+
+/// Redirects requests for a host to another base URL, e.g. a mirror or a local mock server.
+///
+/// URLs are still built for the real hosts; `https://ddnet.org/players/?json2=x`
+/// with `ddnet.org` mapped to `http://localhost:8080` is sent to
+/// `http://localhost:8080/players/?json2=x`. Unmapped hosts are left alone.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+///
+/// let urls = BaseUrls::new().with("ddnet.org", "http://localhost:8080/");
+/// assert_eq!(urls.rewrite("https://ddnet.org/status"), "http://localhost:8080/status");
+/// assert_eq!(urls.rewrite("https://ddstats.tw/maps/json"), "https://ddstats.tw/maps/json");
+///
+/// let urls = BaseUrls::all("http://127.0.0.1:9000");
+/// assert_eq!(
+///     urls.rewrite("https://master1.ddnet.org/ddnet/15/servers.json"),
+///     "http://127.0.0.1:9000/master1.ddnet.org/ddnet/15/servers.json",
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BaseUrls {
+    hosts: HashMap<String, String>,
+}
+
+impl BaseUrls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends requests for `host` to `base` instead.
+    pub fn with(mut self, host: impl Into<String>, base: impl Into<String>) -> Self {
+        let base = base.into().trim_end_matches('/').to_string();
+        self.hosts.insert(host.into(), base);
+        self
+    }
+
+    /// Maps every host in [`API_HOSTS`] to `{origin}/{host}`, so one server can answer for all of them.
+    pub fn all(origin: &str) -> Self {
+        let origin = origin.trim_end_matches('/');
+        API_HOSTS.iter().fold(Self::new(), |urls, host| {
+            urls.with(*host, format!("{origin}/{host}"))
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    pub fn rewrite(&self, url: &str) -> String {
+        let Some((_, rest)) = url.split_once("://") else {
+            return url.to_string();
+        };
+        let end = rest.find(['/', '?']).unwrap_or(rest.len());
+        match self.hosts.get(&rest[..end]) {
+            Some(base) => format!("{base}{}", &rest[end..]),
+            None => url.to_string(),
+        }
+    }
+}
@@ -0,0 +1,565 @@
+use crate::demo::header::{c_str, parse_sha256, put_c_str, sha256_hex};
+use crate::demo::huffman;
+use crate::demo::message::Message;
+use crate::demo::snapshot::*;
+use crate::demo::varint;
+use crate::error::{Error, Result};
+use crate::mapfile::prelude::{ints_to_str, MapFile};
+use std::path::Path;
+
+const MAGIC: &[u8; 7] = b"TWDEMO\0";
+const HEADER_SIZE: usize = 176;
+const MAX_TIMELINE_MARKERS: usize = 64;
+/// Extension UUID that precedes the map sha256 in version 6 demos.
+const SHA256_EXTENSION: [u8; 16] = [
+    0x6b, 0xe6, 0xda, 0x4a, 0xce, 0xbd, 0x38, 0x0c, 0x9b, 0x5b, 0x12, 0x89, 0xc8, 0x42, 0xd7, 0x80,
+];
+
+const VERSION_OLD: u8 = 3;
+const VERSION_TICK_COMPRESSION: u8 = 5;
+const VERSION_SHA256: u8 = 6;
+
+const CHUNKTYPEFLAG_TICKMARKER: u8 = 0x80;
+const CHUNKTICKFLAG_KEYFRAME: u8 = 0x40;
+const CHUNKTICKFLAG_TICK_COMPRESSED: u8 = 0x20;
+const CHUNKMASK_TICK: u8 = 0x1f;
+const CHUNKMASK_TICK_LEGACY: u8 = 0x3f;
+const CHUNKMASK_TYPE: u8 = 0x60;
+const CHUNKMASK_SIZE: u8 = 0x1f;
+const CHUNKTYPE_SNAPSHOT: u8 = 1;
+const CHUNKTYPE_MESSAGE: u8 = 2;
+const CHUNKTYPE_DELTA: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemoHeader {
+    /// Demo format version; DDNet writes 6.
+    pub version: u8,
+    pub net_version: String,
+    /// `"client"` or `"server"`.
+    pub kind: String,
+    /// Length in seconds.
+    pub length: i32,
+    pub timestamp: String,
+}
+
+impl Default for DemoHeader {
+    fn default() -> Self {
+        Self {
+            version: VERSION_SHA256,
+            net_version: "0.6 626fce9a778df4d4".to_string(),
+            kind: "client".to_string(),
+            length: 0,
+            timestamp: String::new(),
+        }
+    }
+}
+
+/// The map a demo was recorded on, embedded in the file.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DemoMap {
+    pub name: String,
+    pub crc: u32,
+    /// Lowercase hex, only present in version 6 demos.
+    pub sha256: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl DemoMap {
+    pub fn parse(&self) -> Result<MapFile> {
+        MapFile::parse(&self.data)
+    }
+}
+
+/// One chunk of the demo stream, already Huffman- and int-unpacked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chunk {
+    Tick {
+        tick: i32,
+        keyframe: bool,
+    },
+    /// A full `CSnapshot`, see [`Snapshot::parse`].
+    Snapshot(Vec<i32>),
+    /// A `CSnapshotDelta` against the previous snapshot, see [`Snapshot::apply_delta`].
+    Delta(Vec<i32>),
+    /// A packed network message, see [`Message::parse`].
+    Message(Vec<u8>),
+}
+
+/// A Teeworlds/DDNet `.demo` file.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::demo::*;
+///
+/// let mut snapshot = Snapshot::default();
+/// let mut character = vec![0; 22];
+/// character[1] = 64;
+/// character[2] = 96;
+/// snapshot.items.insert((NETOBJTYPE_CHARACTER, 0), character.clone());
+///
+/// let mut moved = snapshot.clone();
+/// character[1] = 80;
+/// moved.items.insert((NETOBJTYPE_CHARACTER, 0), character);
+///
+/// let chat = Message::Chat { team: 0, client_id: 0, text: "gl".to_string() };
+/// let demo = Demo {
+///     map: DemoMap { name: "Tutorial".to_string(), ..Default::default() },
+///     markers: vec![100],
+///     chunks: vec![
+///         Chunk::Tick { tick: 100, keyframe: true },
+///         Chunk::Snapshot(snapshot.to_ints()),
+///         Chunk::Message(chat.to_bytes()),
+///         Chunk::Tick { tick: 101, keyframe: false },
+///         Chunk::Delta(snapshot.create_delta(&moved)),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let parsed = Demo::parse(&demo.to_bytes().unwrap()).unwrap();
+/// assert_eq!(parsed.map.name, "Tutorial");
+/// assert_eq!(parsed.markers, vec![100]);
+///
+/// let ticks: Vec<DemoTick> = parsed.ticks().collect::<Result<_, _>>().unwrap();
+/// assert_eq!(ticks.len(), 2);
+/// assert_eq!(ticks[0].chat().collect::<Vec<_>>(), vec![(0, "gl")]);
+/// assert_eq!((ticks[1].tick, ticks[1].players[0].x), (101, 80));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Demo {
+    pub header: DemoHeader,
+    pub map: DemoMap,
+    /// Timeline marker ticks.
+    pub markers: Vec<i32>,
+    pub chunks: Vec<Chunk>,
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| Error::Format(format!("demo truncated at offset {}", self.pos)))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn i32_be(&mut self) -> Result<i32> {
+        let b = self.take(4)?;
+        Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+}
+
+impl Demo {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parses a demo of version 3 to 6.
+    ///
+    /// # Examples
+    ///
+    /// A version 6 header written byte by byte, followed by a full and a
+    /// compressed tick marker:
+    ///
+    /// ```rust
+    /// use ddapi_rs::prelude::demo::*;
+    ///
+    /// let field = |s: &str, size: usize| {
+    ///     let mut bytes = s.as_bytes().to_vec();
+    ///     bytes.resize(size, 0);
+    ///     bytes
+    /// };
+    /// let mut buf = b"TWDEMO\0\x06".to_vec();
+    /// buf.extend(field("0.6 626fce9a778df4d4", 64));
+    /// buf.extend(field("Tutorial", 64));
+    /// buf.extend(0i32.to_be_bytes()); // map size
+    /// buf.extend(0x12345678i32.to_be_bytes()); // map crc
+    /// buf.extend(field("client", 8));
+    /// buf.extend(83i32.to_be_bytes()); // length in seconds
+    /// buf.extend(field("2024-05-06_20-00-00", 20));
+    /// buf.extend(2i32.to_be_bytes()); // timeline markers
+    /// buf.extend([250i32, 500].iter().flat_map(|t| t.to_be_bytes()));
+    /// buf.extend([0; 62 * 4]);
+    /// buf.extend([0xc0, 0, 0, 0, 100]); // keyframe at tick 100
+    /// buf.push(0xa1); // one tick later
+    ///
+    /// let demo = Demo::parse(&buf).unwrap();
+    /// assert_eq!(demo.header.net_version, "0.6 626fce9a778df4d4");
+    /// assert_eq!((demo.header.kind.as_str(), demo.header.length), ("client", 83));
+    /// assert_eq!(demo.header.timestamp, "2024-05-06_20-00-00");
+    /// assert_eq!((demo.map.name.as_str(), demo.map.crc, demo.map.sha256), ("Tutorial", 0x12345678, None));
+    /// assert_eq!(demo.markers, vec![250, 500]);
+    /// assert_eq!(
+    ///     demo.chunks,
+    ///     vec![Chunk::Tick { tick: 100, keyframe: true }, Chunk::Tick { tick: 101, keyframe: false }]
+    /// );
+    /// ```
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        let mut r = Reader { buf, pos: 0 };
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(Error::Format("missing demo signature".to_string()));
+        }
+        let version = r.take(1)?[0];
+        if !(VERSION_OLD..=VERSION_SHA256).contains(&version) {
+            return Err(Error::Format(format!("unsupported demo version {version}")));
+        }
+
+        let net_version = c_str(r.take(64)?);
+        let map_name = c_str(r.take(64)?);
+        let map_size = usize::try_from(r.i32_be()?)
+            .map_err(|_| Error::Format("negative map size".to_string()))?;
+        let map_crc = r.i32_be()? as u32;
+        let kind = c_str(r.take(8)?);
+        let length = r.i32_be()?;
+        let timestamp = c_str(r.take(20)?);
+        debug_assert_eq!(r.pos, HEADER_SIZE);
+
+        let mut markers = Vec::new();
+        if version > VERSION_OLD {
+            let num = r.i32_be()?.clamp(0, MAX_TIMELINE_MARKERS as i32) as usize;
+            for i in 0..MAX_TIMELINE_MARKERS {
+                let tick = r.i32_be()?;
+                if i < num {
+                    markers.push(tick);
+                }
+            }
+        }
+
+        let mut sha256 = None;
+        if version >= VERSION_SHA256 && buf.get(r.pos..r.pos + 16) == Some(&SHA256_EXTENSION) {
+            r.take(16)?;
+            sha256 = Some(sha256_hex(r.take(32)?));
+        }
+
+        let map_data = r.take(map_size)?.to_vec();
+
+        let mut chunks = Vec::new();
+        let mut tick = 0;
+        while !r.is_empty() {
+            let chunk = r.take(1)?[0];
+            if chunk & CHUNKTYPEFLAG_TICKMARKER != 0 {
+                let keyframe = chunk & CHUNKTICKFLAG_KEYFRAME != 0;
+                let legacy_delta = chunk & CHUNKMASK_TICK_LEGACY;
+                if version < VERSION_TICK_COMPRESSION && legacy_delta != 0 {
+                    tick += legacy_delta as i32;
+                } else if version >= VERSION_TICK_COMPRESSION
+                    && chunk & CHUNKTICKFLAG_TICK_COMPRESSED != 0
+                {
+                    tick += (chunk & CHUNKMASK_TICK) as i32;
+                } else {
+                    tick = r.i32_be()?;
+                }
+                chunks.push(Chunk::Tick { tick, keyframe });
+                continue;
+            }
+
+            let kind = (chunk & CHUNKMASK_TYPE) >> 5;
+            let size = match chunk & CHUNKMASK_SIZE {
+                30 => r.take(1)?[0] as usize,
+                31 => {
+                    let b = r.take(2)?;
+                    u16::from_le_bytes([b[0], b[1]]) as usize
+                }
+                size => size as usize,
+            };
+            let ints = varint::decompress(&huffman::decompress(r.take(size)?)?)?;
+            chunks.push(match kind {
+                CHUNKTYPE_SNAPSHOT => Chunk::Snapshot(ints),
+                CHUNKTYPE_DELTA => Chunk::Delta(ints),
+                CHUNKTYPE_MESSAGE => {
+                    Chunk::Message(ints.iter().flat_map(|i| i.to_le_bytes()).collect())
+                }
+                _ => {
+                    return Err(Error::Format(format!(
+                        "unknown demo chunk type {kind} at offset {}",
+                        r.pos
+                    )))
+                }
+            });
+        }
+
+        Ok(Self {
+            header: DemoHeader {
+                version,
+                net_version,
+                kind,
+                length,
+                timestamp,
+            },
+            map: DemoMap {
+                name: map_name,
+                crc: map_crc,
+                sha256,
+                data: map_data,
+            },
+            markers,
+            chunks,
+        })
+    }
+
+    /// Serializes the demo in the format given by `header.version`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let version = self.header.version;
+        let map_size = i32::try_from(self.map.data.len())
+            .map_err(|_| Error::Format("embedded map too large".to_string()))?;
+
+        let mut out = Vec::with_capacity(HEADER_SIZE + self.map.data.len());
+        out.extend_from_slice(MAGIC);
+        out.push(version);
+        put_c_str(&mut out, &self.header.net_version, 64);
+        put_c_str(&mut out, &self.map.name, 64);
+        out.extend_from_slice(&map_size.to_be_bytes());
+        out.extend_from_slice(&self.map.crc.to_be_bytes());
+        put_c_str(&mut out, &self.header.kind, 8);
+        out.extend_from_slice(&self.header.length.to_be_bytes());
+        put_c_str(&mut out, &self.header.timestamp, 20);
+
+        if version > VERSION_OLD {
+            let num = self.markers.len().min(MAX_TIMELINE_MARKERS);
+            out.extend_from_slice(&(num as i32).to_be_bytes());
+            for i in 0..MAX_TIMELINE_MARKERS {
+                let tick = self.markers.get(i).copied().unwrap_or(0);
+                out.extend_from_slice(&tick.to_be_bytes());
+            }
+        }
+
+        if let Some(sha256) = self
+            .map
+            .sha256
+            .as_deref()
+            .filter(|_| version >= VERSION_SHA256)
+        {
+            let digest = parse_sha256(sha256)?;
+            out.extend_from_slice(&SHA256_EXTENSION);
+            out.extend_from_slice(&digest);
+        }
+
+        out.extend_from_slice(&self.map.data);
+
+        let mut last_tick: Option<i32> = None;
+        for chunk in &self.chunks {
+            let (kind, ints) = match chunk {
+                Chunk::Tick { tick, keyframe } => {
+                    let delta = last_tick.map(|last| tick - last);
+                    match delta {
+                        Some(delta @ 0..=31)
+                            if !keyframe && version >= VERSION_TICK_COMPRESSION =>
+                        {
+                            out.push(
+                                CHUNKTYPEFLAG_TICKMARKER
+                                    | CHUNKTICKFLAG_TICK_COMPRESSED
+                                    | delta as u8,
+                            );
+                        }
+                        _ => {
+                            let flag = if *keyframe { CHUNKTICKFLAG_KEYFRAME } else { 0 };
+                            out.push(CHUNKTYPEFLAG_TICKMARKER | flag);
+                            out.extend_from_slice(&tick.to_be_bytes());
+                        }
+                    }
+                    last_tick = Some(*tick);
+                    continue;
+                }
+                Chunk::Snapshot(ints) => (CHUNKTYPE_SNAPSHOT, ints.clone()),
+                Chunk::Delta(ints) => (CHUNKTYPE_DELTA, ints.clone()),
+                Chunk::Message(bytes) => (
+                    CHUNKTYPE_MESSAGE,
+                    bytes
+                        .chunks(4)
+                        .map(|c| {
+                            let mut word = [0; 4];
+                            word[..c.len()].copy_from_slice(c);
+                            i32::from_le_bytes(word)
+                        })
+                        .collect(),
+                ),
+            };
+
+            let data = huffman::compress(&varint::compress(&ints));
+            let header = CHUNKMASK_TYPE & (kind << 5);
+            match data.len() {
+                size @ 0..=29 => out.push(header | size as u8),
+                size @ 30..=255 => out.extend_from_slice(&[header | 30, size as u8]),
+                size => {
+                    let size = u16::try_from(size)
+                        .map_err(|_| Error::Format("demo chunk too large".to_string()))?;
+                    out.push(header | 31);
+                    out.extend_from_slice(&size.to_le_bytes());
+                }
+            }
+            out.extend_from_slice(&data);
+        }
+
+        Ok(out)
+    }
+
+    /// Replays the chunk stream tick by tick.
+    pub fn ticks(&self) -> Ticks<'_> {
+        Ticks {
+            chunks: self.chunks.iter(),
+            snapshot: Snapshot::default(),
+            pending: None,
+            failed: false,
+        }
+    }
+}
+
+/// Position and state of one player's character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerState {
+    pub client_id: u16,
+    /// From the player's client info, if it was in the snapshot.
+    pub name: Option<String>,
+    /// Position in world units (32 per tile).
+    pub x: i32,
+    pub y: i32,
+    /// Velocity, multiplied by 256.
+    pub vel_x: i32,
+    pub vel_y: i32,
+    pub weapon: i32,
+}
+
+/// A snapshot event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    Explosion { x: i32, y: i32 },
+    Spawn { x: i32, y: i32 },
+    HammerHit { x: i32, y: i32 },
+    Death { x: i32, y: i32, client_id: i32 },
+    Sound { x: i32, y: i32, sound_id: i32 },
+    DamageIndicator { x: i32, y: i32, angle: i32 },
+}
+
+/// Everything recorded for one tick.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct DemoTick {
+    pub tick: i32,
+    pub keyframe: bool,
+    pub players: Vec<PlayerState>,
+    pub events: Vec<GameEvent>,
+    pub messages: Vec<Message>,
+}
+
+impl DemoTick {
+    /// Chat lines of this tick as `(client_id, text)`.
+    pub fn chat(&self) -> impl Iterator<Item = (i32, &str)> {
+        self.messages.iter().filter_map(|message| match message {
+            Message::Chat {
+                client_id, text, ..
+            } => Some((*client_id, text.as_str())),
+            _ => None,
+        })
+    }
+
+    fn fill(&mut self, snapshot: &Snapshot) {
+        self.players = snapshot
+            .items_of_type(NETOBJTYPE_CHARACTER)
+            .filter(|(_, data)| data.len() >= 20)
+            .map(|(client_id, data)| PlayerState {
+                client_id,
+                name: snapshot
+                    .item(NETOBJTYPE_CLIENTINFO, client_id)
+                    .and_then(|info| info.get(..4))
+                    .map(ints_to_str),
+                x: data[1],
+                y: data[2],
+                vel_x: data[3],
+                vel_y: data[4],
+                weapon: data[19],
+            })
+            .collect();
+
+        self.events = snapshot
+            .items
+            .iter()
+            .filter(|(_, data)| data.len() >= 2)
+            .filter_map(|(&(type_id, _), data)| {
+                let (x, y) = (data[0], data[1]);
+                let third = data.get(2).copied().unwrap_or_default();
+                Some(match type_id {
+                    NETEVENTTYPE_EXPLOSION => GameEvent::Explosion { x, y },
+                    NETEVENTTYPE_SPAWN => GameEvent::Spawn { x, y },
+                    NETEVENTTYPE_HAMMERHIT => GameEvent::HammerHit { x, y },
+                    NETEVENTTYPE_DEATH => GameEvent::Death {
+                        x,
+                        y,
+                        client_id: third,
+                    },
+                    NETEVENTTYPE_SOUNDWORLD => GameEvent::Sound {
+                        x,
+                        y,
+                        sound_id: third,
+                    },
+                    NETEVENTTYPE_DAMAGEIND => GameEvent::DamageIndicator { x, y, angle: third },
+                    _ => return None,
+                })
+            })
+            .collect();
+    }
+}
+
+/// Iterator over the ticks of a [`Demo`], see [`Demo::ticks`].
+pub struct Ticks<'a> {
+    chunks: std::slice::Iter<'a, Chunk>,
+    snapshot: Snapshot,
+    pending: Option<DemoTick>,
+    failed: bool,
+}
+
+impl Ticks<'_> {
+    fn finish(&self, mut tick: DemoTick) -> DemoTick {
+        tick.fill(&self.snapshot);
+        tick
+    }
+}
+
+impl Iterator for Ticks<'_> {
+    type Item = Result<DemoTick>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        for chunk in self.chunks.by_ref() {
+            let applied = match chunk {
+                Chunk::Tick { tick, keyframe } => {
+                    let next = DemoTick {
+                        tick: *tick,
+                        keyframe: *keyframe,
+                        ..Default::default()
+                    };
+                    if let Some(done) = self.pending.replace(next) {
+                        return Some(Ok(self.finish(done)));
+                    }
+                    continue;
+                }
+                Chunk::Snapshot(ints) => Snapshot::parse(ints),
+                Chunk::Delta(ints) => self.snapshot.apply_delta(ints),
+                Chunk::Message(bytes) => {
+                    self.pending
+                        .get_or_insert_with(DemoTick::default)
+                        .messages
+                        .push(Message::parse(bytes));
+                    continue;
+                }
+            };
+            match applied {
+                Ok(snapshot) => self.snapshot = snapshot,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        self.pending.take().map(|done| Ok(self.finish(done)))
+    }
+}
@@ -0,0 +1,377 @@
+use crate::demo::header::{c_str, parse_sha256, put_c_str, sha256_hex};
+use crate::demo::huffman;
+use crate::demo::varint;
+use crate::error::{Error, Result};
+use crate::mapfile::prelude::{ints_to_str, str_to_ints};
+use crate::prelude::RaceTime;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"TWGHOST\0";
+const CURRENT_VERSION: u8 = 6;
+const MIN_VERSION: u8 = 4;
+const OWNER_SIZE: usize = 16;
+const MAP_SIZE: usize = 64;
+const MAX_ITEMS_PER_CHUNK: usize = 50;
+
+pub const GHOSTDATA_TYPE_SKIN: u8 = 0;
+pub const GHOSTDATA_TYPE_CHARACTER_NO_TICK: u8 = 1;
+pub const GHOSTDATA_TYPE_CHARACTER: u8 = 2;
+pub const GHOSTDATA_TYPE_START_TICK: u8 = 3;
+
+fn item_size(type_id: u8) -> Option<usize> {
+    match type_id {
+        GHOSTDATA_TYPE_SKIN => Some(9),
+        GHOSTDATA_TYPE_CHARACTER_NO_TICK => Some(11),
+        GHOSTDATA_TYPE_CHARACTER => Some(12),
+        GHOSTDATA_TYPE_START_TICK => Some(1),
+        _ => None,
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct GhostSkin {
+    pub name: String,
+    pub use_custom_color: bool,
+    pub color_body: i32,
+    pub color_feet: i32,
+}
+
+impl GhostSkin {
+    fn from_ints(data: &[i32]) -> Self {
+        Self {
+            name: ints_to_str(&data[..6]),
+            use_custom_color: data[6] != 0,
+            color_body: data[7],
+            color_feet: data[8],
+        }
+    }
+
+    fn to_ints(&self) -> Vec<i32> {
+        let mut data = str_to_ints(&self.name, 6);
+        data.extend([
+            self.use_custom_color as i32,
+            self.color_body,
+            self.color_feet,
+        ]);
+        data
+    }
+}
+
+/// One recorded tick of the ghost's character.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GhostCharacter {
+    /// Position in world units (32 per tile).
+    pub x: i32,
+    pub y: i32,
+    pub vel_x: i32,
+    pub vel_y: i32,
+    pub angle: i32,
+    pub direction: i32,
+    pub weapon: i32,
+    pub hook_state: i32,
+    pub hook_x: i32,
+    pub hook_y: i32,
+    pub attack_tick: i32,
+    /// Game tick, absent in ghosts recorded by older clients.
+    pub tick: Option<i32>,
+}
+
+impl GhostCharacter {
+    fn from_ints(data: &[i32]) -> Self {
+        Self {
+            x: data[0],
+            y: data[1],
+            vel_x: data[2],
+            vel_y: data[3],
+            angle: data[4],
+            direction: data[5],
+            weapon: data[6],
+            hook_state: data[7],
+            hook_x: data[8],
+            hook_y: data[9],
+            attack_tick: data[10],
+            tick: data.get(11).copied(),
+        }
+    }
+
+    fn to_ints(self) -> Vec<i32> {
+        let mut data = vec![
+            self.x,
+            self.y,
+            self.vel_x,
+            self.vel_y,
+            self.angle,
+            self.direction,
+            self.weapon,
+            self.hook_state,
+            self.hook_x,
+            self.hook_y,
+            self.attack_tick,
+        ];
+        data.extend(self.tick);
+        data
+    }
+}
+
+/// A DDNet `.gho` ghost: a player's race path and skin.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::demo::*;
+/// use ddapi_rs::prelude::RaceTime;
+///
+/// let ghost = Ghost {
+///     owner: "nameless tee".to_string(),
+///     map: "Tutorial".to_string(),
+///     map_sha256: Some("ab".repeat(32)),
+///     time: RaceTime::from_millis(83_420),
+///     skin: Some(GhostSkin { name: "default".to_string(), ..Default::default() }),
+///     start_tick: Some(500),
+///     path: (0..120)
+///         .map(|i| GhostCharacter { x: i * 8, y: 320, tick: Some(500 + i), ..Default::default() })
+///         .collect(),
+///     ..Default::default()
+/// };
+///
+/// let parsed = Ghost::parse(&ghost.to_bytes().unwrap()).unwrap();
+/// assert_eq!(parsed, ghost);
+/// assert_eq!(parsed.time_secs(), 83.42);
+/// assert_eq!(parsed.time.to_string(), "01:23.42");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ghost {
+    pub version: u8,
+    pub owner: String,
+    pub map: String,
+    /// Map crc, stored by ghosts older than version 6.
+    pub map_crc: Option<u32>,
+    /// Lowercase hex, stored by version 6 ghosts; written as zeroes when `None`.
+    pub map_sha256: Option<String>,
+    /// Finish time recorded in the header.
+    pub time: RaceTime,
+    pub skin: Option<GhostSkin>,
+    pub start_tick: Option<i32>,
+    pub path: Vec<GhostCharacter>,
+}
+
+impl Default for Ghost {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            owner: String::new(),
+            map: String::new(),
+            map_crc: None,
+            map_sha256: None,
+            time: RaceTime::ZERO,
+            skin: None,
+            start_tick: None,
+            path: Vec::new(),
+        }
+    }
+}
+
+fn truncated(offset: usize) -> Error {
+    Error::Format(format!("ghost truncated at offset {offset}"))
+}
+
+impl Ghost {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    /// Parses a ghost of version 4 to 6.
+    ///
+    /// # Examples
+    ///
+    /// A version 6 header written byte by byte, without any recorded ticks:
+    ///
+    /// ```rust
+    /// use ddapi_rs::prelude::demo::*;
+    ///
+    /// let field = |s: &str, size: usize| {
+    ///     let mut bytes = s.as_bytes().to_vec();
+    ///     bytes.resize(size, 0);
+    ///     bytes
+    /// };
+    /// let mut buf = b"TWGHOST\0\x06".to_vec();
+    /// buf.extend(field("nameless tee", 16));
+    /// buf.extend(field("Tutorial", 64));
+    /// buf.extend([0; 4]); // zeroes where older versions store the map crc
+    /// buf.extend(0i32.to_be_bytes()); // number of ticks
+    /// buf.extend(83_420i32.to_be_bytes()); // time in milliseconds
+    /// buf.extend([0xab; 32]); // map sha256
+    ///
+    /// let ghost = Ghost::parse(&buf).unwrap();
+    /// assert_eq!((ghost.version, ghost.owner.as_str(), ghost.map.as_str()), (6, "nameless tee", "Tutorial"));
+    /// assert_eq!(ghost.time.to_string(), "01:23.42");
+    /// assert_eq!(ghost.map_sha256, Some("ab".repeat(32)));
+    /// assert_eq!(ghost.map_crc, None);
+    /// assert!(ghost.path.is_empty());
+    /// ```
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        let header =
+            |start: usize, len: usize| buf.get(start..start + len).ok_or_else(|| truncated(start));
+        let be = |start: usize| -> Result<i32> {
+            let b = header(start, 4)?;
+            Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        if header(0, MAGIC.len())? != MAGIC {
+            return Err(Error::Format("missing ghost signature".to_string()));
+        }
+        let version = header(8, 1)?[0];
+        if !(MIN_VERSION..=CURRENT_VERSION).contains(&version) {
+            return Err(Error::Format(format!(
+                "unsupported ghost version {version}"
+            )));
+        }
+
+        let owner = c_str(header(9, OWNER_SIZE)?);
+        let map = c_str(header(9 + OWNER_SIZE, MAP_SIZE)?);
+        let crc_offset = 9 + OWNER_SIZE + MAP_SIZE;
+        let map_crc = (version < 6)
+            .then(|| be(crc_offset))
+            .transpose()?
+            .map(|crc| crc as u32);
+        let time = RaceTime::from_millis(be(crc_offset + 8)? as i64);
+        let mut pos = crc_offset + 12;
+        let map_sha256 = if version >= 6 {
+            pos += 32;
+            let sha256 = header(pos - 32, 32)?;
+            // An all-zero digest means the recording client did not know it.
+            (sha256 != [0; 32]).then(|| sha256_hex(sha256))
+        } else {
+            None
+        };
+
+        let mut ghost = Self {
+            version,
+            owner,
+            map,
+            map_crc,
+            map_sha256,
+            time,
+            ..Default::default()
+        };
+
+        let mut last: Option<(u8, Vec<i32>)> = None;
+        while pos < buf.len() {
+            let chunk = header(pos, 4)?;
+            let (type_id, num_items) = (chunk[0], chunk[1] as usize);
+            let size = u16::from_be_bytes([chunk[2], chunk[3]]) as usize;
+            let item_size = item_size(type_id)
+                .ok_or_else(|| Error::Format(format!("unknown ghost item type {type_id}")))?;
+            let data = varint::decompress(&huffman::decompress(header(pos + 4, size)?)?)?;
+            if data.len() < num_items * item_size {
+                return Err(Error::Format(format!(
+                    "ghost chunk at offset {pos} holds fewer than {num_items} items"
+                )));
+            }
+            pos += 4 + size;
+
+            // Version 4 ghosts keep diffing across chunk boundaries.
+            if version != 4 {
+                last = None;
+            }
+            for raw in data.chunks_exact(item_size).take(num_items) {
+                let item = match &last {
+                    Some((last_type, past)) if *last_type == type_id => past
+                        .iter()
+                        .zip(raw)
+                        .map(|(a, b)| a.wrapping_add(*b))
+                        .collect(),
+                    _ => raw.to_vec(),
+                };
+                match type_id {
+                    GHOSTDATA_TYPE_SKIN => ghost.skin = Some(GhostSkin::from_ints(&item)),
+                    GHOSTDATA_TYPE_START_TICK => ghost.start_tick = Some(item[0]),
+                    _ => ghost.path.push(GhostCharacter::from_ints(&item)),
+                }
+                last = Some((type_id, item));
+            }
+        }
+
+        Ok(ghost)
+    }
+
+    /// Serializes as a version 6 ghost (or the older layout when `version` is 4 or 5).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(self.version);
+        put_c_str(&mut out, &self.owner, OWNER_SIZE);
+        put_c_str(&mut out, &self.map, MAP_SIZE);
+        let crc = if self.version < 6 {
+            self.map_crc.unwrap_or_default()
+        } else {
+            0
+        };
+        out.extend_from_slice(&crc.to_be_bytes());
+        let num_ticks = i32::try_from(self.path.len())
+            .map_err(|_| Error::Format("ghost path too long".to_string()))?;
+        out.extend_from_slice(&num_ticks.to_be_bytes());
+        out.extend_from_slice(&(self.time.as_millis() as i32).to_be_bytes());
+        if self.version >= 6 {
+            // Unknown digests are written as zeros, like the client does.
+            let digest = match self.map_sha256.as_deref().filter(|s| !s.is_empty()) {
+                Some(sha256) => parse_sha256(sha256)?,
+                None => [0; 32],
+            };
+            out.extend_from_slice(&digest);
+        }
+
+        let mut items: Vec<(u8, Vec<i32>)> = Vec::new();
+        if let Some(skin) = &self.skin {
+            items.push((GHOSTDATA_TYPE_SKIN, skin.to_ints()));
+        }
+        if let Some(tick) = self.start_tick {
+            items.push((GHOSTDATA_TYPE_START_TICK, vec![tick]));
+        }
+        for character in &self.path {
+            let type_id = if character.tick.is_some() {
+                GHOSTDATA_TYPE_CHARACTER
+            } else {
+                GHOSTDATA_TYPE_CHARACTER_NO_TICK
+            };
+            items.push((type_id, character.to_ints()));
+        }
+
+        let mut start = 0;
+        while start < items.len() {
+            let type_id = items[start].0;
+            let end = items[start..]
+                .iter()
+                .take(MAX_ITEMS_PER_CHUNK)
+                .position(|(t, _)| *t != type_id)
+                .map_or((start + MAX_ITEMS_PER_CHUNK).min(items.len()), |n| {
+                    start + n
+                });
+
+            let mut data = Vec::new();
+            for index in start..end {
+                let item = &items[index].1;
+                let diff_base = index > start || (self.version == 4 && index > 0);
+                match index.checked_sub(1).and_then(|i| items.get(i)) {
+                    Some((last_type, past)) if diff_base && *last_type == type_id => {
+                        data.extend(past.iter().zip(item).map(|(a, b)| b.wrapping_sub(*a)))
+                    }
+                    _ => data.extend_from_slice(item),
+                }
+            }
+            let packed = huffman::compress(&varint::compress(&data));
+            let size = u16::try_from(packed.len())
+                .map_err(|_| Error::Format("ghost chunk too large".to_string()))?;
+            out.extend_from_slice(&[type_id, (end - start) as u8]);
+            out.extend_from_slice(&size.to_be_bytes());
+            out.extend_from_slice(&packed);
+            start = end;
+        }
+
+        Ok(out)
+    }
+
+    /// Finish time in seconds, comparable with `DRank::time`.
+    pub fn time_secs(&self) -> f64 {
+        self.time.as_secs_f64()
+    }
+}
@@ -0,0 +1,36 @@
+//! Fields shared by the demo and ghost file headers.
+
+use crate::error::{Error, Result};
+
+/// Reads a zero-terminated string from a fixed-size field.
+pub(crate) fn c_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Writes `s` into a fixed-size field of `size` bytes, truncated to leave room for the terminator.
+pub(crate) fn put_c_str(out: &mut Vec<u8>, s: &str, size: usize) {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.truncate(size - 1);
+    bytes.resize(size, 0);
+    out.extend_from_slice(&bytes);
+}
+
+/// Formats a map sha256 digest as lowercase hex.
+pub(crate) fn sha256_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parses a map sha256 written as 64 hex digits.
+pub(crate) fn parse_sha256(hex: &str) -> Result<[u8; 32]> {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>();
+    bytes
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| Error::Format(format!("invalid map sha256 {hex:?}")))
+}
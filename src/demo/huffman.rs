@@ -0,0 +1,161 @@
+//! The static Huffman code Teeworlds uses for network packets, demos and ghosts.
+//!
+//! # Examples
+//! ```rust
+//! use ddapi_rs::prelude::demo::huffman;
+//!
+//! let data = b"\0\0\0\x01hello".to_vec();
+//! let packed = huffman::compress(&data);
+//! assert_eq!(huffman::decompress(&packed).unwrap(), data);
+//! ```
+
+use crate::error::{Error, Result};
+use std::cmp::Reverse;
+use std::sync::OnceLock;
+
+const EOF_SYMBOL: usize = 256;
+const MAX_SYMBOLS: usize = EOF_SYMBOL + 1;
+const MAX_NODES: usize = MAX_SYMBOLS * 2 - 1;
+const NO_LEAF: u16 = 0xffff;
+
+/// Symbol frequencies the Teeworlds code was built from; the EOF entry is replaced by 1.
+#[rustfmt::skip]
+const FREQ_TABLE: [u32; MAX_SYMBOLS] = [
+    1 << 30, 4545, 2657, 431, 1950, 919, 444, 482, 2244, 617, 838, 542, 715, 1814, 304, 240, 754, 212, 647, 186,
+    283, 131, 146, 166, 543, 164, 167, 136, 179, 859, 363, 113, 157, 154, 204, 108, 137, 180, 202, 176,
+    872, 404, 168, 134, 151, 111, 113, 109, 120, 126, 129, 100, 41, 20, 16, 22, 18, 18, 17, 19,
+    16, 37, 13, 21, 362, 166, 99, 78, 95, 88, 81, 70, 83, 284, 91, 187, 77, 68, 52, 68,
+    59, 66, 61, 638, 71, 157, 50, 46, 69, 43, 11, 24, 13, 19, 10, 12, 12, 20, 14, 9,
+    20, 20, 10, 10, 15, 15, 12, 12, 7, 19, 15, 14, 13, 18, 35, 19, 17, 14, 8, 5,
+    15, 17, 9, 15, 14, 18, 8, 10, 2173, 134, 157, 68, 188, 60, 170, 60, 194, 62, 175, 71,
+    148, 67, 167, 78, 211, 67, 156, 69, 1674, 90, 174, 53, 147, 89, 181, 51, 174, 63, 163, 80,
+    167, 94, 128, 122, 223, 153, 218, 77, 200, 110, 190, 73, 174, 69, 145, 66, 277, 143, 141, 60,
+    136, 53, 180, 57, 142, 57, 158, 61, 166, 112, 152, 92, 26, 22, 21, 28, 20, 26, 30, 21,
+    32, 27, 20, 17, 23, 21, 30, 22, 22, 21, 27, 25, 17, 27, 23, 18, 39, 26, 15, 21,
+    12, 18, 18, 27, 20, 18, 15, 19, 11, 17, 33, 12, 18, 15, 19, 18, 16, 26, 17, 18,
+    9, 10, 25, 22, 22, 17, 20, 16, 6, 16, 15, 20, 14, 18, 24, 335, 1517,
+];
+
+#[derive(Clone, Copy)]
+struct Node {
+    bits: u32,
+    num_bits: u32,
+    leafs: [u16; 2],
+}
+
+struct Tree {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+impl Tree {
+    /// Builds the tree exactly like `CHuffman::ConstructTree`, so codes match the game.
+    fn new() -> Self {
+        let mut nodes = vec![
+            Node {
+                bits: 0,
+                num_bits: u32::MAX,
+                leafs: [NO_LEAF; 2],
+            };
+            MAX_SYMBOLS
+        ];
+        nodes.reserve(MAX_NODES - MAX_SYMBOLS);
+
+        let mut left: Vec<(u32, u16)> = (0..MAX_SYMBOLS)
+            .map(|i| {
+                let freq = if i == EOF_SYMBOL { 1 } else { FREQ_TABLE[i] };
+                (freq, i as u16)
+            })
+            .collect();
+
+        while left.len() > 1 {
+            // Stable, descending: the same order the original bubble sort produces.
+            left.sort_by_key(|&(freq, _)| Reverse(freq));
+            let (last_freq, last_id) = left.pop().unwrap_or_default();
+            let second = left.last_mut().expect("at least two nodes left");
+            nodes.push(Node {
+                bits: 0,
+                num_bits: 0,
+                leafs: [last_id, second.1],
+            });
+            *second = (second.0 + last_freq, (nodes.len() - 1) as u16);
+        }
+
+        let root = nodes.len() - 1;
+        let mut tree = Self { nodes, root };
+        tree.set_bits(root, 0, 0);
+        tree
+    }
+
+    fn set_bits(&mut self, node: usize, bits: u32, depth: u32) {
+        let [zero, one] = self.nodes[node].leafs;
+        if one != NO_LEAF {
+            self.set_bits(one as usize, bits | (1 << depth), depth + 1);
+        }
+        if zero != NO_LEAF {
+            self.set_bits(zero as usize, bits, depth + 1);
+        }
+        let node = &mut self.nodes[node];
+        if node.num_bits != 0 {
+            node.bits = bits;
+            node.num_bits = depth;
+        }
+    }
+}
+
+fn tree() -> &'static Tree {
+    static TREE: OnceLock<Tree> = OnceLock::new();
+    TREE.get_or_init(Tree::new)
+}
+
+/// Huffman-encodes `input`, appending the EOF symbol.
+pub fn compress(input: &[u8]) -> Vec<u8> {
+    let tree = tree();
+    let mut out = Vec::with_capacity(input.len());
+    let mut bits: u64 = 0;
+    let mut bit_count: u32 = 0;
+
+    let symbols = input
+        .iter()
+        .map(|&b| b as usize)
+        .chain(std::iter::once(EOF_SYMBOL));
+    for symbol in symbols {
+        let node = &tree.nodes[symbol];
+        bits |= (node.bits as u64) << bit_count;
+        bit_count += node.num_bits;
+        while bit_count >= 8 {
+            out.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    }
+    // The game always flushes one more byte, even when it is empty.
+    out.push(bits as u8);
+    out
+}
+
+/// Decodes Huffman data up to its EOF symbol.
+pub fn decompress(input: &[u8]) -> Result<Vec<u8>> {
+    let tree = tree();
+    let mut out = Vec::with_capacity(input.len() * 2);
+    let mut node = tree.root;
+
+    for (i, byte) in input.iter().enumerate() {
+        for bit in 0..8 {
+            node = tree.nodes[node].leafs[((byte >> bit) & 1) as usize] as usize;
+            if node < MAX_SYMBOLS {
+                if node == EOF_SYMBOL {
+                    return Ok(out);
+                }
+                out.push(node as u8);
+                node = tree.root;
+            } else if node == NO_LEAF as usize {
+                return Err(Error::Format(format!("invalid huffman code at byte {i}")));
+            }
+        }
+    }
+
+    Err(Error::Format(
+        "huffman data ended without EOF symbol".to_string(),
+    ))
+}
@@ -0,0 +1,115 @@
+use crate::demo::varint;
+
+pub const NETMSGTYPE_SV_BROADCAST: i32 = 2;
+pub const NETMSGTYPE_SV_CHAT: i32 = 3;
+
+/// A network message recorded in a demo.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::demo::Message;
+///
+/// let chat = Message::Chat { team: 0, client_id: 3, text: "gg".to_string() };
+/// assert_eq!(Message::parse(&chat.to_bytes()), chat);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// `SV_CHAT`. `client_id` is `-1` for server messages.
+    Chat {
+        team: i32,
+        client_id: i32,
+        text: String,
+    },
+    /// `SV_BROADCAST`.
+    Broadcast(String),
+    /// Any other message, with its payload after the message id.
+    Other {
+        id: i32,
+        system: bool,
+        data: Vec<u8>,
+    },
+}
+
+impl Message {
+    /// Parses a packed message. Messages that fail to unpack are kept as [`Message::Other`].
+    pub fn parse(data: &[u8]) -> Self {
+        let mut input = data;
+        let Ok(header) = varint::unpack(&mut input) else {
+            return Message::Other {
+                id: -1,
+                system: false,
+                data: data.to_vec(),
+            };
+        };
+        let (id, system) = (header >> 1, header & 1 == 1);
+        let other = || Message::Other {
+            id,
+            system,
+            data: input.to_vec(),
+        };
+
+        if system {
+            return other();
+        }
+        let mut rest = input;
+        match id {
+            NETMSGTYPE_SV_CHAT => {
+                let (Ok(team), Ok(client_id)) =
+                    (varint::unpack(&mut rest), varint::unpack(&mut rest))
+                else {
+                    return other();
+                };
+                match unpack_string(&mut rest) {
+                    Some(text) => Message::Chat {
+                        team,
+                        client_id,
+                        text,
+                    },
+                    None => other(),
+                }
+            }
+            NETMSGTYPE_SV_BROADCAST => unpack_string(&mut rest)
+                .map(Message::Broadcast)
+                .unwrap_or_else(other),
+            _ => other(),
+        }
+    }
+
+    /// Packs the message the way the server sends it.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Message::Chat {
+                team,
+                client_id,
+                text,
+            } => {
+                varint::pack(NETMSGTYPE_SV_CHAT << 1, &mut out);
+                varint::pack(*team, &mut out);
+                varint::pack(*client_id, &mut out);
+                pack_string(text, &mut out);
+            }
+            Message::Broadcast(text) => {
+                varint::pack(NETMSGTYPE_SV_BROADCAST << 1, &mut out);
+                pack_string(text, &mut out);
+            }
+            Message::Other { id, system, data } => {
+                varint::pack(id << 1 | *system as i32, &mut out);
+                out.extend_from_slice(data);
+            }
+        }
+        out
+    }
+}
+
+fn unpack_string(input: &mut &[u8]) -> Option<String> {
+    let end = input.iter().position(|&b| b == 0)?;
+    let text = String::from_utf8_lossy(&input[..end]).into_owned();
+    *input = &input[end + 1..];
+    Some(text)
+}
+
+fn pack_string(text: &str, out: &mut Vec<u8>) {
+    out.extend(text.bytes().filter(|&b| b != 0));
+    out.push(0);
+}
@@ -0,0 +1,16 @@
+mod demofile;
+mod ghost;
+mod header;
+pub mod huffman;
+mod message;
+mod snapshot;
+pub mod varint;
+
+pub mod prelude {
+    pub use crate::demo::demofile::*;
+    pub use crate::demo::ghost::*;
+    pub use crate::demo::huffman;
+    pub use crate::demo::message::*;
+    pub use crate::demo::snapshot::*;
+    pub use crate::demo::varint;
+}
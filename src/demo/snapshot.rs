@@ -0,0 +1,193 @@
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+
+pub const NETOBJTYPE_CHARACTER: u16 = 9;
+pub const NETOBJTYPE_PLAYERINFO: u16 = 10;
+pub const NETOBJTYPE_CLIENTINFO: u16 = 11;
+pub const NETEVENTTYPE_EXPLOSION: u16 = 14;
+pub const NETEVENTTYPE_SPAWN: u16 = 15;
+pub const NETEVENTTYPE_HAMMERHIT: u16 = 16;
+pub const NETEVENTTYPE_DEATH: u16 = 17;
+pub const NETEVENTTYPE_SOUNDWORLD: u16 = 19;
+pub const NETEVENTTYPE_DAMAGEIND: u16 = 20;
+
+/// Item sizes (in ints) of the 0.6 protocol; `0` means the size is sent in the delta.
+const ITEM_SIZES: [usize; 21] = [
+    0, 10, 6, 5, 4, 3, 8, 4, 15, 22, 5, 17, 3, 2, 2, 2, 2, 3, 3, 3, 3,
+];
+
+fn item_size(type_id: u16) -> Option<usize> {
+    ITEM_SIZES
+        .get(type_id as usize)
+        .copied()
+        .filter(|&size| size != 0)
+}
+
+/// The state of the game world at one tick: items keyed by `(type, id)`.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::demo::Snapshot;
+///
+/// let mut old = Snapshot::default();
+/// old.items.insert((9, 0), vec![0; 22]);
+/// old.items.insert((11, 0), vec![0; 17]);
+///
+/// let mut new = old.clone();
+/// new.items.get_mut(&(9, 0)).unwrap()[1] = 32 * 10;
+/// new.items.remove(&(11, 0));
+/// new.items.insert((300, 1), vec![7, 8]);
+///
+/// let delta = old.create_delta(&new);
+/// assert_eq!(old.apply_delta(&delta).unwrap(), new);
+/// assert_eq!(Snapshot::parse(&new.to_ints()).unwrap(), new);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub items: BTreeMap<(u16, u16), Vec<i32>>,
+}
+
+impl Snapshot {
+    /// Parses a full snapshot (`CSnapshot`) from its unpacked integers.
+    pub fn parse(ints: &[i32]) -> Result<Self> {
+        let malformed = |what: &str| Error::Format(format!("malformed snapshot: {what}"));
+        let header = ints.get(..2).ok_or_else(|| malformed("missing header"))?;
+        let data_size = usize::try_from(header[0]).map_err(|_| malformed("negative size"))?;
+        let num_items = usize::try_from(header[1]).map_err(|_| malformed("negative count"))?;
+        let offsets = ints
+            .get(2..2 + num_items)
+            .ok_or_else(|| malformed("offsets truncated"))?;
+        let data = ints
+            .get(2 + num_items..2 + num_items + data_size / 4)
+            .ok_or_else(|| malformed("data truncated"))?;
+
+        let mut items = BTreeMap::new();
+        for (i, &offset) in offsets.iter().enumerate() {
+            let start = usize::try_from(offset).map_err(|_| malformed("negative offset"))? / 4;
+            let end = match offsets.get(i + 1) {
+                Some(&next) => usize::try_from(next).map_err(|_| malformed("negative offset"))? / 4,
+                None => data.len(),
+            };
+            let item = data
+                .get(start..end)
+                .filter(|item| !item.is_empty())
+                .ok_or_else(|| malformed("item out of bounds"))?;
+            let key = item[0] as u32;
+            items.insert(((key >> 16) as u16, key as u16), item[1..].to_vec());
+        }
+        Ok(Self { items })
+    }
+
+    /// Serializes as a `CSnapshot`, the inverse of [`Snapshot::parse`].
+    pub fn to_ints(&self) -> Vec<i32> {
+        let data_len: usize = self.items.values().map(|data| data.len() + 1).sum();
+        let mut out = Vec::with_capacity(2 + self.items.len() + data_len);
+        out.push((data_len * 4) as i32);
+        out.push(self.items.len() as i32);
+        let mut offset = 0;
+        for data in self.items.values() {
+            out.push((offset * 4) as i32);
+            offset += data.len() + 1;
+        }
+        for (&(type_id, id), data) in &self.items {
+            out.push(key(type_id, id));
+            out.extend_from_slice(data);
+        }
+        out
+    }
+
+    /// Applies a `CSnapshotDelta` to this snapshot, returning the new one.
+    pub fn apply_delta(&self, delta: &[i32]) -> Result<Self> {
+        let malformed = |what: &str| Error::Format(format!("malformed snapshot delta: {what}"));
+        let mut reader = delta.iter().copied();
+        let mut next = |what: &str| reader.next().ok_or_else(|| malformed(what));
+
+        let num_deleted = next("header")?;
+        let num_updates = next("header")?;
+        let _num_temp = next("header")?;
+
+        let mut items = self.items.clone();
+        for _ in 0..num_deleted {
+            let key = next("deleted keys")? as u32;
+            items.remove(&((key >> 16) as u16, key as u16));
+        }
+
+        for _ in 0..num_updates {
+            let type_id = next("item type")?;
+            let id = next("item id")?;
+            let (Ok(type_id), Ok(id)) = (u16::try_from(type_id), u16::try_from(id)) else {
+                return Err(malformed("item key out of range"));
+            };
+            let size = match item_size(type_id) {
+                Some(size) => size,
+                None => usize::try_from(next("item size")?)
+                    .map_err(|_| malformed("negative item size"))?,
+            };
+            let diff = (0..size)
+                .map(|_| next("item data"))
+                .collect::<Result<Vec<i32>>>()?;
+            let data = match self.items.get(&(type_id, id)) {
+                Some(past) if past.len() == size => past
+                    .iter()
+                    .zip(&diff)
+                    .map(|(a, b)| a.wrapping_add(*b))
+                    .collect(),
+                _ => diff,
+            };
+            items.insert((type_id, id), data);
+        }
+
+        Ok(Self { items })
+    }
+
+    /// Builds the delta that turns `self` into `to`, as the server would send it.
+    pub fn create_delta(&self, to: &Snapshot) -> Vec<i32> {
+        let deleted: Vec<i32> = self
+            .items
+            .keys()
+            .filter(|k| !to.items.contains_key(k))
+            .map(|&(type_id, id)| key(type_id, id))
+            .collect();
+
+        let mut updates = Vec::new();
+        let mut num_updates = 0;
+        for (&(type_id, id), data) in &to.items {
+            let diff: Vec<i32> = match self.items.get(&(type_id, id)) {
+                Some(past) if past == data => continue,
+                Some(past) if past.len() == data.len() => past
+                    .iter()
+                    .zip(data)
+                    .map(|(a, b)| b.wrapping_sub(*a))
+                    .collect(),
+                _ => data.clone(),
+            };
+            updates.push(type_id as i32);
+            updates.push(id as i32);
+            if item_size(type_id).is_none() {
+                updates.push(data.len() as i32);
+            }
+            updates.extend(diff);
+            num_updates += 1;
+        }
+
+        let mut out = vec![deleted.len() as i32, num_updates, 0];
+        out.extend(deleted);
+        out.extend(updates);
+        out
+    }
+
+    pub fn item(&self, type_id: u16, id: u16) -> Option<&[i32]> {
+        self.items.get(&(type_id, id)).map(Vec::as_slice)
+    }
+
+    /// All items of one type as `(id, data)`, ordered by id.
+    pub fn items_of_type(&self, type_id: u16) -> impl Iterator<Item = (u16, &[i32])> {
+        self.items
+            .range((type_id, 0)..=(type_id, u16::MAX))
+            .map(|(&(_, id), data)| (id, data.as_slice()))
+    }
+}
+
+fn key(type_id: u16, id: u16) -> i32 {
+    ((type_id as u32) << 16 | id as u32) as i32
+}
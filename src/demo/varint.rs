@@ -0,0 +1,72 @@
+//! Teeworlds variable-length integers (`CVariableInt`).
+//!
+//! The first byte holds a continuation bit, the sign bit and 6 value bits;
+//! every following byte holds a continuation bit and 7 value bits. Negative
+//! numbers are stored as their bitwise complement.
+//!
+//! # Examples
+//! ```rust
+//! use ddapi_rs::prelude::demo::varint;
+//!
+//! let ints = vec![0, 63, 64, -1, i32::MIN, i32::MAX];
+//! let packed = varint::compress(&ints);
+//! assert_eq!(varint::decompress(&packed).unwrap(), ints);
+//!
+//! let mut buf = Vec::new();
+//! varint::pack(-64, &mut buf);
+//! assert_eq!(buf, [0x7f]);
+//! ```
+
+use crate::error::{Error, Result};
+
+/// Appends `value` to `out`.
+pub fn pack(value: i32, out: &mut Vec<u8>) {
+    let mut byte = ((value >> 25) & 0x40) as u8;
+    let mut rest = (value ^ (value >> 31)) as u32;
+    byte |= (rest & 0x3f) as u8;
+    rest >>= 6;
+    while rest != 0 {
+        out.push(byte | 0x80);
+        byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+    }
+    out.push(byte);
+}
+
+/// Reads one integer from the front of `input`, advancing it.
+pub fn unpack(input: &mut &[u8]) -> Result<i32> {
+    let mut value: u32 = 0;
+    let mut sign = 0;
+    for i in 0..5 {
+        let (&byte, rest) = input
+            .split_first()
+            .ok_or_else(|| Error::Format("variable int truncated".to_string()))?;
+        *input = rest;
+        if i == 0 {
+            sign = (byte >> 6) & 1;
+            value = (byte & 0x3f) as u32;
+        } else {
+            value |= ((byte & 0x7f) as u32) << (6 + 7 * (i - 1));
+        }
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok((value as i32) ^ -(sign as i32))
+}
+
+pub fn compress(ints: &[i32]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(ints.len());
+    for &value in ints {
+        pack(value, &mut out);
+    }
+    out
+}
+
+pub fn decompress(mut input: &[u8]) -> Result<Vec<i32>> {
+    let mut out = Vec::with_capacity(input.len());
+    while !input.is_empty() {
+        out.push(unpack(&mut input)?);
+    }
+    Ok(out)
+}
@@ -1,30 +1,350 @@
+use reqwest::StatusCode;
 use std::fmt;
+use std::time::Duration;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The kind of API endpoint a request went to, derived from its URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Endpoint {
+    Master,
+    Skins,
+    Player,
+    Query,
+    Map,
+    QueryMap,
+    QueryMapper,
+    LatestFinishes,
+    Releases,
+    Status,
+    Feed,
+    MapFile,
+    StatsPlayer,
+    StatsMap,
+    StatsMaps,
+    StatsProfile,
+    StatsSearchPlayers,
+    StatsSearchMaps,
+    StatsLeaderboard,
+    StatsServers,
+    /// A URL the crate does not build itself, or none at all.
+    Other,
+}
+
+impl Endpoint {
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::*;
+    ///
+    /// assert_eq!(Endpoint::from_url("https://ddnet.org/players/?json2=nameless%20tee"), Endpoint::Player);
+    /// assert_eq!(Endpoint::from_url("https://ddstats.tw/search/maps/json?q=Fox"), Endpoint::StatsSearchMaps);
+    /// assert_eq!(Endpoint::from_url("https://example.com/"), Endpoint::Other);
+    /// ```
+    pub fn from_url(url: &str) -> Self {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let end = rest.find(['/', '?']).unwrap_or(rest.len());
+        let (host, path) = rest.split_at(end);
+        match host {
+            "skins.ddnet.org" => Endpoint::Skins,
+            "maps.ddnet.org" => Endpoint::MapFile,
+            host if host.starts_with("master") && host.ends_with(".ddnet.org") => Endpoint::Master,
+            "ddnet.org" => match path {
+                p if p.starts_with("/players/?json2=") => Endpoint::Player,
+                p if p.starts_with("/players/?query=") => Endpoint::Query,
+                p if p.starts_with("/maps/?json=") => Endpoint::Map,
+                p if p.starts_with("/maps/?query=") => Endpoint::QueryMap,
+                p if p.starts_with("/maps/?qmapper=") => Endpoint::QueryMapper,
+                p if p.starts_with("/maps/?latest=") => Endpoint::LatestFinishes,
+                p if p.starts_with("/releases/maps.json") => Endpoint::Releases,
+                p if p.starts_with("/status/json") => Endpoint::Status,
+                p if p.ends_with("/feed/") => Endpoint::Feed,
+                _ => Endpoint::Other,
+            },
+            "ddstats.tw" => match path {
+                p if p.starts_with("/player/json") => Endpoint::StatsPlayer,
+                p if p.starts_with("/map/json") => Endpoint::StatsMap,
+                p if p.starts_with("/maps/json") => Endpoint::StatsMaps,
+                p if p.starts_with("/profile/json") => Endpoint::StatsProfile,
+                p if p.starts_with("/search/json") => Endpoint::StatsSearchPlayers,
+                p if p.starts_with("/search/maps/json") => Endpoint::StatsSearchMaps,
+                p if p.starts_with("/leaderboard/json") => Endpoint::StatsLeaderboard,
+                p if p.starts_with("/servers/json") => Endpoint::StatsServers,
+                _ => Endpoint::Other,
+            },
+            _ => Endpoint::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endpoint::Master => "master",
+            Endpoint::Skins => "skins",
+            Endpoint::Player => "player",
+            Endpoint::Query => "player query",
+            Endpoint::Map => "map",
+            Endpoint::QueryMap => "map query",
+            Endpoint::QueryMapper => "mapper query",
+            Endpoint::LatestFinishes => "latest finishes",
+            Endpoint::Releases => "releases",
+            Endpoint::Status => "status",
+            Endpoint::Feed => "feed",
+            Endpoint::MapFile => "map file",
+            Endpoint::StatsPlayer => "ddstats player",
+            Endpoint::StatsMap => "ddstats map",
+            Endpoint::StatsMaps => "ddstats maps",
+            Endpoint::StatsProfile => "ddstats profile",
+            Endpoint::StatsSearchPlayers => "ddstats player search",
+            Endpoint::StatsSearchMaps => "ddstats map search",
+            Endpoint::StatsLeaderboard => "ddstats leaderboard",
+            Endpoint::StatsServers => "ddstats servers",
+            Endpoint::Other => "request",
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors of the crate.
+///
+/// Request errors carry the [`Endpoint`] and the URL as built by the crate
+/// (before any [`BaseUrls`](crate::api::BaseUrls) rewrite). The URL is empty
+/// for errors that did not come from a request.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::*;
+///
+/// struct Drifted;
+///
+/// impl Transport for Drifted {
+///     fn send(&self, _: HttpRequest) -> TransportFuture<'_, HttpResponse> {
+///         Box::pin(async {
+///             Ok(HttpResponse::ok(
+///                 r#"[{"timestamp": 1715025600, "map": "Tutorial", "name": "nameless tee", "time": "30.0", "server": "GER"}]"#,
+///             ))
+///         })
+///     }
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let api = DDnetClient::new_with_transport(Drifted);
+/// let error = api.latest_finish().await.unwrap_err();
+/// assert_eq!(error.endpoint(), Some(Endpoint::LatestFinishes));
+/// assert_eq!(error.url(), Some("https://ddnet.org/maps/?latest=0"));
+/// assert_eq!(error.json_path(), Some("[0].time"));
+/// # });
+/// ```
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    NotFound,
-    EmptyBody,
+    /// DDNet answered `{}`, or DDStats answered "player not found".
+    NotFound {
+        endpoint: Endpoint,
+        url: String,
+    },
+    EmptyBody {
+        endpoint: Endpoint,
+        url: String,
+    },
     /// DDStats sometimes returns `{ "error": "..." }` with HTTP 200.
-    RemoteMessage(String),
-    Http(reqwest::Error),
+    RemoteMessage {
+        endpoint: Endpoint,
+        url: String,
+        message: String,
+    },
+    /// The request could not be sent or its response not be read.
+    Http {
+        endpoint: Endpoint,
+        url: String,
+        source: reqwest::Error,
+    },
+    Timeout {
+        endpoint: Endpoint,
+        url: String,
+    },
+    /// HTTP 429, with the delay from `Retry-After` if the server sent one.
+    RateLimited {
+        endpoint: Endpoint,
+        url: String,
+        retry_after: Option<Duration>,
+    },
     HttpStatus {
-        status: reqwest::StatusCode,
+        endpoint: Endpoint,
+        url: String,
+        status: StatusCode,
         body: String,
     },
-    Json(serde_json::Error),
+    /// The response did not match the schema; `path` points at the offending value, e.g. `servers[3].info.clients`.
+    Json {
+        endpoint: Endpoint,
+        url: String,
+        path: String,
+        source: serde_json::Error,
+    },
+    Io(std::io::Error),
+    /// A binary file (map, demo, ghost) is truncated or malformed.
+    Format(String),
+    /// A download did not match its expected sha256.
+    ChecksumMismatch {
+        expected: String,
+        actual: String,
+    },
+    /// A download exceeded the allowed size in bytes.
+    TooLarge {
+        limit: u64,
+    },
+    /// A replayed request has no recorded fixture.
+    MissingFixture(String),
+}
+
+impl Error {
+    pub fn endpoint(&self) -> Option<Endpoint> {
+        match self {
+            Error::NotFound { endpoint, .. }
+            | Error::EmptyBody { endpoint, .. }
+            | Error::RemoteMessage { endpoint, .. }
+            | Error::Http { endpoint, .. }
+            | Error::Timeout { endpoint, .. }
+            | Error::RateLimited { endpoint, .. }
+            | Error::HttpStatus { endpoint, .. }
+            | Error::Json { endpoint, .. } => Some(*endpoint),
+            _ => None,
+        }
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Error::NotFound { url, .. }
+            | Error::EmptyBody { url, .. }
+            | Error::RemoteMessage { url, .. }
+            | Error::Http { url, .. }
+            | Error::Timeout { url, .. }
+            | Error::RateLimited { url, .. }
+            | Error::HttpStatus { url, .. }
+            | Error::Json { url, .. } => Some(url.as_str()).filter(|url| !url.is_empty()),
+            Error::MissingFixture(url) => Some(url),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::HttpStatus { status, .. } => Some(*status),
+            Error::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Error::Http { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Where in the JSON document deserialization failed.
+    pub fn json_path(&self) -> Option<&str> {
+        match self {
+            Error::Json { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// The player, map or page does not exist (including HTTP 404).
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::NotFound { .. }) || self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// Whether the same request may succeed later: timeouts, rate limits,
+    /// connection failures, empty bodies and 5xx/408 statuses.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::*;
+    ///
+    /// let url = "https://ddnet.org/players/?json2=nameless%20tee";
+    /// let timeout = Error::Timeout { endpoint: Endpoint::from_url(url), url: url.to_string() };
+    /// assert!(timeout.is_retryable() && !timeout.is_not_found());
+    ///
+    /// let not_found = Error::NotFound { endpoint: Endpoint::Player, url: url.to_string() };
+    /// assert!(not_found.is_not_found() && !not_found.is_retryable());
+    /// assert_eq!(not_found.to_string(), "player not found: https://ddnet.org/players/?json2=nameless%20tee");
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Timeout { .. } | Error::RateLimited { .. } | Error::EmptyBody { .. } => true,
+            Error::Http { source, .. } => {
+                source.is_connect()
+                    || source.is_timeout()
+                    || source.is_request()
+                    || source.is_body()
+            }
+            Error::HttpStatus { status, .. } => {
+                status.is_server_error() || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            _ => false,
+        }
+    }
+
+    /// Attaches the request `url` and its endpoint to errors converted without them.
+    pub(crate) fn with_url(mut self, request_url: &str) -> Self {
+        match &mut self {
+            Error::NotFound { endpoint, url }
+            | Error::EmptyBody { endpoint, url }
+            | Error::RemoteMessage { endpoint, url, .. }
+            | Error::Http { endpoint, url, .. }
+            | Error::Timeout { endpoint, url }
+            | Error::RateLimited { endpoint, url, .. }
+            | Error::HttpStatus { endpoint, url, .. }
+            | Error::Json { endpoint, url, .. } => {
+                *endpoint = Endpoint::from_url(request_url);
+                *url = request_url.to_string();
+            }
+            Error::MissingFixture(url) => *url = request_url.to_string(),
+            _ => {}
+        }
+        self
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::NotFound => write!(f, "Not found"),
-            Error::EmptyBody => write!(f, "Empty response body"),
-            Error::RemoteMessage(msg) => write!(f, "{msg}"),
-            Error::Http(e) => write!(f, "HTTP error: {e}"),
-            Error::HttpStatus { status, body } => write!(f, "HTTP status {status}: {body}"),
-            Error::Json(e) => write!(f, "JSON error: {e}"),
+            Error::NotFound { endpoint, url } => write!(f, "{endpoint} not found: {url}"),
+            Error::EmptyBody { endpoint, url } => {
+                write!(f, "Empty response body from {endpoint}: {url}")
+            }
+            Error::RemoteMessage { message, .. } => write!(f, "{message}"),
+            Error::Http { url, source, .. } => write!(f, "HTTP error for {url}: {source}"),
+            Error::Timeout { endpoint, url } => write!(f, "{endpoint} timed out: {url}"),
+            Error::RateLimited {
+                url, retry_after, ..
+            } => match retry_after {
+                Some(after) => write!(f, "Rate limited on {url}, retry after {after:?}"),
+                None => write!(f, "Rate limited on {url}"),
+            },
+            Error::HttpStatus {
+                url, status, body, ..
+            } => write!(f, "HTTP status {status} for {url}: {body}"),
+            Error::Json {
+                endpoint,
+                path,
+                source,
+                ..
+            } => write!(f, "JSON error in {endpoint} response at {path}: {source}"),
+            Error::Io(e) => write!(f, "IO error: {e}"),
+            Error::Format(msg) => write!(f, "Invalid file format: {msg}"),
+            Error::ChecksumMismatch { expected, actual } => {
+                write!(f, "Checksum mismatch: expected {expected}, got {actual}")
+            }
+            Error::TooLarge { limit } => write!(f, "Download exceeds {limit} bytes"),
+            Error::MissingFixture(url) => write!(f, "No recorded fixture for {url}"),
         }
     }
 }
@@ -32,8 +352,9 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Http(e) => Some(e),
-            Error::Json(e) => Some(e),
+            Error::Http { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            Error::Io(e) => Some(e),
             _ => None,
         }
     }
@@ -41,12 +362,44 @@ impl std::error::Error for Error {
 
 impl From<reqwest::Error> for Error {
     fn from(value: reqwest::Error) -> Self {
-        Error::Http(value)
+        let url = value.url().map(|url| url.to_string()).unwrap_or_default();
+        let endpoint = Endpoint::from_url(&url);
+        if value.is_timeout() {
+            Error::Timeout { endpoint, url }
+        } else {
+            Error::Http {
+                endpoint,
+                url,
+                source: value,
+            }
+        }
     }
 }
 
 impl From<serde_json::Error> for Error {
     fn from(value: serde_json::Error) -> Self {
-        Error::Json(value)
+        Error::Json {
+            endpoint: Endpoint::Other,
+            url: String::new(),
+            path: String::new(),
+            source: value,
+        }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(value: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Error::Json {
+            endpoint: Endpoint::Other,
+            url: String::new(),
+            path: value.path().to_string(),
+            source: value.into_inner(),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
     }
 }
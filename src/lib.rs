@@ -1,12 +1,20 @@
 mod api;
+#[cfg(feature = "demo")]
+mod demo;
 mod error;
+#[cfg(feature = "mapfile")]
+mod mapfile;
 mod scheme;
 mod util;
 
 pub mod prelude {
     pub use crate::api::DDApi;
+    pub use crate::api::{BaseUrls, API_HOSTS, DEFAULT_TIMEOUT};
     pub use crate::api::{DDnetClient, DDstatsClient};
+    pub use crate::api::{Drift, DriftKind, DriftReport};
+    pub use crate::api::{HttpRequest, HttpResponse, ReqwestTransport, Transport, TransportFuture};
     pub use crate::error::*;
+    pub use crate::scheme::{Category, ParseCategoryError};
     pub use crate::util::prelude::*;
 
     #[cfg(feature = "ddnet")]
@@ -15,9 +23,15 @@ pub mod prelude {
     #[cfg(feature = "ddstats")]
     pub use crate::api::ddstats::DDstats;
 
+    #[cfg(feature = "download")]
+    pub use crate::api::download::MapDownload;
+
     #[cfg(feature = "ddnet")]
     pub mod ddnet {
         pub use crate::api::ddnet::*;
+        #[cfg(feature = "feeds")]
+        pub use crate::api::feed::*;
+        pub use crate::api::records::*;
         pub use crate::scheme::ddnet::prelude::*;
     }
 
@@ -26,4 +40,40 @@ pub mod prelude {
         pub use crate::api::ddstats::*;
         pub use crate::scheme::ddstats::*;
     }
+
+    #[cfg(feature = "download")]
+    pub mod download {
+        pub use crate::api::download::*;
+    }
+
+    #[cfg(feature = "blocking")]
+    pub mod blocking {
+        pub use crate::api::blocking::*;
+    }
+
+    #[cfg(feature = "stream")]
+    pub mod stream {
+        pub use crate::api::stream::*;
+        pub use futures_util::{Stream, StreamExt};
+    }
+
+    #[cfg(feature = "fixtures")]
+    pub mod fixtures {
+        pub use crate::api::fixtures::*;
+    }
+
+    #[cfg(feature = "testing")]
+    pub mod testing {
+        pub use crate::api::testing::*;
+    }
+
+    #[cfg(feature = "demo")]
+    pub mod demo {
+        pub use crate::demo::prelude::*;
+    }
+
+    #[cfg(feature = "mapfile")]
+    pub mod mapfile {
+        pub use crate::mapfile::prelude::*;
+    }
 }
@@ -0,0 +1,344 @@
+use crate::error::{Error, Result};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+const HEADER_SIZE: usize = 36;
+/// Largest decompressed data blob accepted, well above any real map's images.
+const MAX_DATA_SIZE: usize = 256 * 1024 * 1024;
+/// Most bytes zlib's deflate can expand a single compressed byte to.
+const MAX_DEFLATE_RATIO: usize = 1032;
+
+/// One item of a datafile: a typed, numbered list of 32-bit integers.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub type_id: u16,
+    pub id: u16,
+    pub data: Vec<i32>,
+}
+
+impl Item {
+    /// Integer at `index`, or `0` when the item is too short (older item versions).
+    pub fn get(&self, index: usize) -> i32 {
+        self.data.get(index).copied().unwrap_or(0)
+    }
+
+    /// Data index stored at `index`, treating negative values as "none".
+    pub fn data_index(&self, index: usize) -> Option<usize> {
+        usize::try_from(*self.data.get(index)?).ok()
+    }
+}
+
+/// The Teeworlds datafile container used by `.map` files.
+///
+/// Holds items sorted by `(type_id, id)` and the data blobs in their
+/// decompressed form. Versions 3 (uncompressed) and 4 (zlib) are read;
+/// [`Datafile::to_bytes`] always writes version 4.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::{Datafile, Item};
+///
+/// let mut file = Datafile::default();
+/// file.items.push(Item { type_id: 0, id: 0, data: vec![1] });
+/// let index = file.add_data(b"hello\0".to_vec());
+///
+/// let bytes = file.to_bytes().unwrap();
+/// let parsed = Datafile::parse(&bytes).unwrap();
+/// assert_eq!(parsed, file);
+/// assert_eq!(parsed.string(index).as_deref(), Some("hello"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Datafile {
+    pub version: i32,
+    pub items: Vec<Item>,
+    pub data: Vec<Vec<u8>>,
+}
+
+impl Default for Datafile {
+    fn default() -> Self {
+        Self {
+            version: 4,
+            items: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn i32_at(&self, offset: usize) -> Result<i32> {
+        let bytes = self
+            .buf
+            .get(offset..offset + 4)
+            .ok_or_else(|| truncated(offset))?;
+        Ok(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn usize_at(&self, offset: usize) -> Result<usize> {
+        let value = self.i32_at(offset)?;
+        usize::try_from(value)
+            .map_err(|_| Error::Format(format!("negative value {value} at offset {offset}")))
+    }
+
+    fn slice(&self, start: usize, len: usize) -> Result<&[u8]> {
+        self.buf
+            .get(start..start.checked_add(len).ok_or_else(|| truncated(start))?)
+            .ok_or_else(|| truncated(start))
+    }
+}
+
+fn truncated(offset: usize) -> Error {
+    Error::Format(format!("datafile truncated at offset {offset}"))
+}
+
+impl Datafile {
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        let r = Reader { buf };
+        let magic = r.slice(0, 4)?;
+        if magic != b"DATA" && magic != b"ATAD" {
+            return Err(Error::Format("missing datafile signature".to_string()));
+        }
+
+        let version = r.i32_at(4)?;
+        if version != 3 && version != 4 {
+            return Err(Error::Format(format!(
+                "unsupported datafile version {version}"
+            )));
+        }
+
+        let num_item_types = r.usize_at(16)?;
+        let num_items = r.usize_at(20)?;
+        let num_data = r.usize_at(24)?;
+        let size_items = r.usize_at(28)?;
+        let size_data = r.usize_at(32)?;
+
+        let types_start = HEADER_SIZE;
+        let item_offsets_start = types_start + num_item_types * 12;
+        let data_offsets_start = item_offsets_start + num_items * 4;
+        let data_sizes_start = data_offsets_start + num_data * 4;
+        let items_start = if version == 4 {
+            data_sizes_start + num_data * 4
+        } else {
+            data_sizes_start
+        };
+        let data_start = items_start + size_items;
+        r.slice(data_start, size_data)?;
+
+        let mut items = Vec::with_capacity(num_items);
+        for i in 0..num_items {
+            let offset = items_start + r.usize_at(item_offsets_start + i * 4)?;
+            let key = r.i32_at(offset)? as u32;
+            let size = r.usize_at(offset + 4)?;
+            if size % 4 != 0 || offset + 8 + size > data_start {
+                return Err(Error::Format(format!("item {i} has invalid size {size}")));
+            }
+            let data = r
+                .slice(offset + 8, size)?
+                .chunks_exact(4)
+                .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                .collect();
+            items.push(Item {
+                type_id: (key >> 16) as u16,
+                id: (key & 0xffff) as u16,
+                data,
+            });
+        }
+
+        let mut data = Vec::with_capacity(num_data);
+        for i in 0..num_data {
+            let start = r.usize_at(data_offsets_start + i * 4)?;
+            let end = if i + 1 < num_data {
+                r.usize_at(data_offsets_start + (i + 1) * 4)?
+            } else {
+                size_data
+            };
+            if start > end || end > size_data {
+                return Err(Error::Format(format!("data {i} has invalid bounds")));
+            }
+            let raw = r.slice(data_start + start, end - start)?;
+
+            if version == 4 {
+                let expected = r.usize_at(data_sizes_start + i * 4)?;
+                // The size is untrusted: bound it before reserving, and never inflate past it.
+                if expected > MAX_DATA_SIZE.min(raw.len().saturating_mul(MAX_DEFLATE_RATIO)) {
+                    return Err(Error::Format(format!(
+                        "data {i} claims {expected} bytes from {} compressed bytes",
+                        raw.len()
+                    )));
+                }
+                let mut out = Vec::with_capacity(expected);
+                ZlibDecoder::new(raw)
+                    .take(expected as u64 + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| Error::Format(format!("data {i} failed to decompress: {e}")))?;
+                if out.len() != expected {
+                    return Err(Error::Format(format!(
+                        "data {i} decompressed to {} bytes, expected {expected}",
+                        out.len()
+                    )));
+                }
+                data.push(out);
+            } else {
+                data.push(raw.to_vec());
+            }
+        }
+
+        Ok(Self {
+            version,
+            items,
+            data,
+        })
+    }
+
+    /// Serializes as a version 4 datafile, sorting items by `(type_id, id)`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut items: Vec<&Item> = self.items.iter().collect();
+        items.sort_by_key(|item| (item.type_id, item.id));
+
+        let mut item_types: Vec<(u16, usize, usize)> = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            match item_types.last_mut() {
+                Some((type_id, _, num)) if *type_id == item.type_id => *num += 1,
+                _ => item_types.push((item.type_id, index, 1)),
+            }
+        }
+
+        let mut compressed = Vec::with_capacity(self.data.len());
+        for blob in &self.data {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(blob)?;
+            compressed.push(encoder.finish()?);
+        }
+
+        let size_items: usize = items.iter().map(|item| 8 + item.data.len() * 4).sum();
+        let size_data: usize = compressed.iter().map(Vec::len).sum();
+        let file_size = HEADER_SIZE
+            + item_types.len() * 12
+            + (items.len() + self.data.len() * 2) * 4
+            + size_items
+            + size_data;
+
+        let to_i32 = |value: usize| {
+            i32::try_from(value).map_err(|_| Error::Format("datafile too large".to_string()))
+        };
+
+        let mut out = Vec::with_capacity(file_size);
+        let put = |out: &mut Vec<u8>, value: i32| out.extend_from_slice(&value.to_le_bytes());
+
+        out.extend_from_slice(b"DATA");
+        put(&mut out, 4);
+        put(&mut out, to_i32(file_size - 16)?);
+        put(&mut out, to_i32(file_size - size_data - 16)?);
+        put(&mut out, to_i32(item_types.len())?);
+        put(&mut out, to_i32(items.len())?);
+        put(&mut out, to_i32(self.data.len())?);
+        put(&mut out, to_i32(size_items)?);
+        put(&mut out, to_i32(size_data)?);
+
+        for (type_id, start, num) in &item_types {
+            put(&mut out, *type_id as i32);
+            put(&mut out, to_i32(*start)?);
+            put(&mut out, to_i32(*num)?);
+        }
+
+        let mut offset = 0;
+        for item in &items {
+            put(&mut out, to_i32(offset)?);
+            offset += 8 + item.data.len() * 4;
+        }
+
+        let mut offset = 0;
+        for blob in &compressed {
+            put(&mut out, to_i32(offset)?);
+            offset += blob.len();
+        }
+        for blob in &self.data {
+            put(&mut out, to_i32(blob.len())?);
+        }
+
+        for item in &items {
+            put(
+                &mut out,
+                ((item.type_id as u32) << 16 | item.id as u32) as i32,
+            );
+            put(&mut out, to_i32(item.data.len() * 4)?);
+            for value in &item.data {
+                put(&mut out, *value);
+            }
+        }
+
+        for blob in &compressed {
+            out.extend_from_slice(blob);
+        }
+
+        Ok(out)
+    }
+
+    /// Appends a data blob and returns its index.
+    pub fn add_data(&mut self, data: Vec<u8>) -> usize {
+        self.data.push(data);
+        self.data.len() - 1
+    }
+
+    pub fn items_of_type(&self, type_id: u16) -> impl Iterator<Item = &Item> {
+        self.items
+            .iter()
+            .filter(move |item| item.type_id == type_id)
+    }
+
+    pub fn item(&self, type_id: u16, id: u16) -> Option<&Item> {
+        self.items
+            .iter()
+            .find(|item| item.type_id == type_id && item.id == id)
+    }
+
+    /// A data blob interpreted as a NUL-terminated string.
+    pub fn string(&self, index: usize) -> Option<String> {
+        let raw = self.data.get(index)?;
+        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+        Some(String::from_utf8_lossy(&raw[..end]).into_owned())
+    }
+}
+
+/// Decodes a name packed into integers, as used for layer, group and envelope names.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::{ints_to_str, str_to_ints};
+///
+/// let packed = str_to_ints("Game", 3);
+/// assert_eq!(ints_to_str(&packed), "Game");
+/// ```
+pub fn ints_to_str(ints: &[i32]) -> String {
+    let mut bytes: Vec<u8> = ints
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .map(|b| b.wrapping_sub(128))
+        .collect();
+    // The last byte is always the terminator, whatever it decodes to.
+    bytes.pop();
+    if let Some(end) = bytes.iter().position(|&b| b == 0) {
+        bytes.truncate(end);
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Packs `s` into `num` integers, truncating so that the result stays NUL-terminated.
+pub fn str_to_ints(s: &str, num: usize) -> Vec<i32> {
+    let mut bytes = s.as_bytes().to_vec();
+    bytes.truncate((num * 4).saturating_sub(1));
+    bytes.resize(num * 4, 0);
+    let mut ints: Vec<i32> = bytes
+        .chunks_exact(4)
+        .map(|c| i32::from_be_bytes([c[0], c[1], c[2], c[3]].map(|b| b.wrapping_add(128))))
+        .collect();
+    if let Some(last) = ints.last_mut() {
+        *last &= !0xff;
+    }
+    ints
+}
@@ -0,0 +1,661 @@
+use crate::error::{Error, Result};
+use crate::mapfile::datafile::{ints_to_str, Datafile, Item};
+use std::path::Path;
+
+pub const ITEMTYPE_VERSION: u16 = 0;
+pub const ITEMTYPE_INFO: u16 = 1;
+pub const ITEMTYPE_IMAGE: u16 = 2;
+pub const ITEMTYPE_ENVELOPE: u16 = 3;
+pub const ITEMTYPE_GROUP: u16 = 4;
+pub const ITEMTYPE_LAYER: u16 = 5;
+pub const ITEMTYPE_ENVPOINTS: u16 = 6;
+pub const ITEMTYPE_SOUND: u16 = 7;
+
+const LAYERTYPE_TILES: i32 = 2;
+const LAYERTYPE_QUADS: i32 = 3;
+const LAYERTYPE_SOUNDS_DEPRECATED: i32 = 9;
+const LAYERTYPE_SOUNDS: i32 = 10;
+
+const TILESLAYERFLAG_GAME: i32 = 1;
+const TILESLAYERFLAG_TELE: i32 = 2;
+const TILESLAYERFLAG_SPEEDUP: i32 = 4;
+const TILESLAYERFLAG_FRONT: i32 = 8;
+const TILESLAYERFLAG_SWITCH: i32 = 16;
+const TILESLAYERFLAG_TUNE: i32 = 32;
+
+const LAYERFLAG_DETAIL: i32 = 1;
+
+/// Optional metadata from the map info item.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct MapInfo {
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub credits: Option<String>,
+    pub license: Option<String>,
+    /// Server settings embedded in the map (DDNet), one command per entry.
+    pub settings: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// External images ship with the client and carry no pixel data.
+    pub external: bool,
+    /// RGBA8 pixels for embedded images.
+    pub data: Option<Vec<u8>>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvPoint {
+    /// Milliseconds since the start of the envelope.
+    pub time: i32,
+    pub curve_type: i32,
+    /// Channel values in 22.10 fixed point.
+    pub values: [i32; 4],
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    pub name: String,
+    /// 1 for sound volume, 3 for position, 4 for colour envelopes.
+    pub channels: i32,
+    pub synchronized: bool,
+    pub points: Vec<EnvPoint>,
+}
+
+/// A map tile: the entity or texture index plus rotation/flip flags.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub index: u8,
+    pub flags: u8,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TeleTile {
+    pub number: u8,
+    pub r#type: u8,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpeedupTile {
+    pub force: u8,
+    pub max_speed: u8,
+    pub r#type: u8,
+    pub angle: i16,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SwitchTile {
+    pub number: u8,
+    pub r#type: u8,
+    pub flags: u8,
+    pub delay: u8,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TuneTile {
+    pub number: u8,
+    pub r#type: u8,
+}
+
+/// Which physics layer a tile layer is, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TileLayerKind {
+    /// A purely visual layer.
+    Tiles,
+    Game,
+    Front,
+    Tele,
+    Speedup,
+    Switch,
+    Tune,
+}
+
+/// Row-major tile data of a tile layer, typed by [`TileLayerKind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileData {
+    Tiles(Vec<Tile>),
+    Tele(Vec<TeleTile>),
+    Speedup(Vec<SpeedupTile>),
+    Switch(Vec<SwitchTile>),
+    Tune(Vec<TuneTile>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileLayer {
+    pub name: String,
+    pub kind: TileLayerKind,
+    pub detail: bool,
+    pub width: u32,
+    pub height: u32,
+    pub color: [i32; 4],
+    pub color_env: Option<usize>,
+    pub image: Option<usize>,
+    pub data: TileData,
+}
+
+impl TileLayer {
+    /// The plain tiles of `Tiles`, `Game` and `Front` layers.
+    pub fn tiles(&self) -> Option<&[Tile]> {
+        match &self.data {
+            TileData::Tiles(tiles) => Some(tiles),
+            _ => None,
+        }
+    }
+
+    /// Tile at column `x`, row `y` of a `Tiles`, `Game` or `Front` layer.
+    pub fn tile(&self, x: u32, y: u32) -> Option<Tile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles()?.get((y * self.width + x) as usize).copied()
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quad {
+    /// Four corners and the pivot, in 22.10 fixed point.
+    pub points: [(i32, i32); 5],
+    pub colors: [[i32; 4]; 4],
+    pub tex_coords: [(i32, i32); 4],
+    pub pos_env: Option<usize>,
+    pub pos_env_offset: i32,
+    pub color_env: Option<usize>,
+    pub color_env_offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuadLayer {
+    pub name: String,
+    pub detail: bool,
+    pub image: Option<usize>,
+    pub quads: Vec<Quad>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Quads(QuadLayer),
+    /// Sound layers are kept by name only.
+    Sounds {
+        name: String,
+    },
+    Unknown {
+        r#type: i32,
+    },
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub offset: (i32, i32),
+    pub parallax: (i32, i32),
+    /// `(x, y, width, height)` when clipping is enabled.
+    pub clip: Option<(i32, i32, i32, i32)>,
+    pub layers: Vec<Layer>,
+}
+
+/// A parsed Teeworlds/DDNet `.map` file.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::*;
+///
+/// // A 2x1 map with a single game layer: one solid tile, one air tile.
+/// let mut file = Datafile::default();
+/// let tiles = file.add_data(vec![1, 0, 0, 0, 0, 0, 0, 0]);
+/// let mut layer = vec![0, 2, 0, 3, 2, 1, 1, 255, 255, 255, 255, -1, 0, -1, tiles as i32];
+/// layer.extend(str_to_ints("Game", 3));
+/// layer.extend([-1; 5]);
+/// file.items.push(Item { type_id: ITEMTYPE_LAYER, id: 0, data: layer });
+/// file.items.push(Item {
+///     type_id: ITEMTYPE_GROUP,
+///     id: 0,
+///     data: vec![3, 0, 0, 100, 100, 0, 1, 0, 0, 0, 0, 0],
+/// });
+///
+/// let map = MapFile::parse(&file.to_bytes().unwrap()).unwrap();
+/// assert_eq!((map.width(), map.height()), (Some(2), Some(1)));
+/// let game = map.game_layer().unwrap();
+/// assert_eq!(game.name, "Game");
+/// assert_eq!(game.tile(0, 0).unwrap().index, 1);
+///
+/// // A header claiming more tiles than the layer data holds is rejected, not allocated.
+/// file.items[0].data[4..6].copy_from_slice(&[i32::MAX, i32::MAX]);
+/// let err = MapFile::parse(&file.to_bytes().unwrap()).unwrap_err();
+/// assert!(matches!(err, ddapi_rs::prelude::Error::Format(_)));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct MapFile {
+    pub info: MapInfo,
+    pub images: Vec<Image>,
+    pub envelopes: Vec<Envelope>,
+    pub groups: Vec<Group>,
+}
+
+impl MapFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&std::fs::read(path)?)
+    }
+
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        Self::from_datafile(&Datafile::parse(buf)?)
+    }
+
+    pub fn from_datafile(file: &Datafile) -> Result<Self> {
+        let info = file
+            .items_of_type(ITEMTYPE_INFO)
+            .next()
+            .map(|item| read_info(file, item))
+            .unwrap_or_default();
+
+        let images = file
+            .items_of_type(ITEMTYPE_IMAGE)
+            .map(|item| read_image(file, item))
+            .collect::<Result<Vec<_>>>()?;
+
+        let envelopes = read_envelopes(file)?;
+
+        let layers: Vec<&Item> = file.items_of_type(ITEMTYPE_LAYER).collect();
+        let groups = file
+            .items_of_type(ITEMTYPE_GROUP)
+            .map(|item| read_group(file, item, &layers))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            info,
+            images,
+            envelopes,
+            groups,
+        })
+    }
+
+    /// Every layer in draw order.
+    pub fn layers(&self) -> impl Iterator<Item = &Layer> {
+        self.groups.iter().flat_map(|g| g.layers.iter())
+    }
+
+    pub fn tile_layers(&self) -> impl Iterator<Item = &TileLayer> {
+        self.layers().filter_map(|layer| match layer {
+            Layer::Tiles(tiles) => Some(tiles),
+            _ => None,
+        })
+    }
+
+    /// The first tile layer of the given physics kind.
+    pub fn physics_layer(&self, kind: TileLayerKind) -> Option<&TileLayer> {
+        self.tile_layers().find(|layer| layer.kind == kind)
+    }
+
+    pub fn game_layer(&self) -> Option<&TileLayer> {
+        self.physics_layer(TileLayerKind::Game)
+    }
+
+    /// Width of the game layer in tiles, as reported by ddnet.org's `Map::width`.
+    pub fn width(&self) -> Option<u32> {
+        self.game_layer().map(|layer| layer.width)
+    }
+
+    /// Height of the game layer in tiles, as reported by ddnet.org's `Map::height`.
+    pub fn height(&self) -> Option<u32> {
+        self.game_layer().map(|layer| layer.height)
+    }
+}
+
+fn optional_index(value: i32) -> Option<usize> {
+    usize::try_from(value).ok()
+}
+
+fn read_info(file: &Datafile, item: &Item) -> MapInfo {
+    let string = |index: usize| item.data_index(index).and_then(|i| file.string(i));
+    let settings = item
+        .data_index(5)
+        .and_then(|i| file.data.get(i))
+        .map(|raw| {
+            raw.split(|&b| b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    MapInfo {
+        author: string(1),
+        version: string(2),
+        credits: string(3),
+        license: string(4),
+        settings,
+    }
+}
+
+fn read_image(file: &Datafile, item: &Item) -> Result<Image> {
+    let width = u32::try_from(item.get(1)).unwrap_or(0);
+    let height = u32::try_from(item.get(2)).unwrap_or(0);
+    let external = item.get(3) != 0;
+    let name = item
+        .data_index(4)
+        .and_then(|i| file.string(i))
+        .unwrap_or_default();
+
+    let data = if external {
+        None
+    } else {
+        let data = item
+            .data_index(5)
+            .and_then(|i| file.data.get(i))
+            .ok_or_else(|| Error::Format(format!("image '{name}' has no pixel data")))?;
+        if data.len() != width as usize * height as usize * 4 {
+            return Err(Error::Format(format!(
+                "image '{name}' has {} bytes, expected {width}x{height} RGBA",
+                data.len()
+            )));
+        }
+        Some(data.clone())
+    };
+
+    Ok(Image {
+        name,
+        width,
+        height,
+        external,
+        data,
+    })
+}
+
+fn read_envelopes(file: &Datafile) -> Result<Vec<Envelope>> {
+    let items: Vec<&Item> = file.items_of_type(ITEMTYPE_ENVELOPE).collect();
+    let points_raw: Vec<i32> = file
+        .items_of_type(ITEMTYPE_ENVPOINTS)
+        .flat_map(|item| item.data.iter().copied())
+        .collect();
+
+    let total_points = items
+        .iter()
+        .map(|item| item.get(2).max(0) as usize + item.get(3).max(0) as usize)
+        .max()
+        .unwrap_or(0);
+    // Version 3 envelopes append bezier tangents to every point.
+    let stride = if total_points > 0 && points_raw.len() == total_points * 22 {
+        22
+    } else {
+        6
+    };
+
+    items
+        .iter()
+        .map(|item| {
+            let start = item.get(2).max(0) as usize;
+            let num = item.get(3).max(0) as usize;
+            let points = (start..start + num)
+                .map(|i| {
+                    let p = points_raw
+                        .get(i * stride..i * stride + 6)
+                        .ok_or_else(|| Error::Format(format!("envelope point {i} missing")))?;
+                    Ok(EnvPoint {
+                        time: p[0],
+                        curve_type: p[1],
+                        values: [p[2], p[3], p[4], p[5]],
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Envelope {
+                name: if item.data.len() >= 12 {
+                    ints_to_str(&item.data[4..12])
+                } else {
+                    String::new()
+                },
+                channels: item.get(1),
+                synchronized: item.get(12) != 0,
+                points,
+            })
+        })
+        .collect()
+}
+
+fn read_group(file: &Datafile, item: &Item, layers: &[&Item]) -> Result<Group> {
+    let start = item.get(5).max(0) as usize;
+    let num = item.get(6).max(0) as usize;
+    let layers = layers
+        .get(start..start + num)
+        .ok_or_else(|| Error::Format(format!("group {} references missing layers", item.id)))?
+        .iter()
+        .map(|layer| read_layer(file, layer))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Group {
+        name: if item.data.len() >= 15 {
+            ints_to_str(&item.data[12..15])
+        } else {
+            String::new()
+        },
+        offset: (item.get(1), item.get(2)),
+        parallax: (item.get(3), item.get(4)),
+        clip: (item.get(7) != 0).then(|| (item.get(8), item.get(9), item.get(10), item.get(11))),
+        layers,
+    })
+}
+
+fn read_layer(file: &Datafile, item: &Item) -> Result<Layer> {
+    let detail = item.get(2) & LAYERFLAG_DETAIL != 0;
+    match item.get(1) {
+        LAYERTYPE_TILES => read_tile_layer(file, item, detail).map(Layer::Tiles),
+        LAYERTYPE_QUADS => read_quad_layer(file, item, detail).map(Layer::Quads),
+        LAYERTYPE_SOUNDS | LAYERTYPE_SOUNDS_DEPRECATED => Ok(Layer::Sounds {
+            name: if item.data.len() >= 10 {
+                ints_to_str(&item.data[7..10])
+            } else {
+                String::new()
+            },
+        }),
+        other => Ok(Layer::Unknown { r#type: other }),
+    }
+}
+
+fn layer_blob<'a>(file: &'a Datafile, item: &Item, index: usize) -> Result<&'a [u8]> {
+    item.data_index(index)
+        .and_then(|i| file.data.get(i))
+        .map(Vec::as_slice)
+        .ok_or_else(|| Error::Format(format!("layer {} references missing data", item.id)))
+}
+
+fn read_tile_layer(file: &Datafile, item: &Item, detail: bool) -> Result<TileLayer> {
+    let version = item.get(3);
+    let width = u32::try_from(item.get(4)).unwrap_or(0);
+    let height = u32::try_from(item.get(5)).unwrap_or(0);
+    let flags = item.get(6);
+    let count = width as usize * height as usize;
+
+    let kind = if flags & TILESLAYERFLAG_GAME != 0 {
+        TileLayerKind::Game
+    } else if flags & TILESLAYERFLAG_TELE != 0 {
+        TileLayerKind::Tele
+    } else if flags & TILESLAYERFLAG_SPEEDUP != 0 {
+        TileLayerKind::Speedup
+    } else if flags & TILESLAYERFLAG_FRONT != 0 {
+        TileLayerKind::Front
+    } else if flags & TILESLAYERFLAG_SWITCH != 0 {
+        TileLayerKind::Switch
+    } else if flags & TILESLAYERFLAG_TUNE != 0 {
+        TileLayerKind::Tune
+    } else {
+        TileLayerKind::Tiles
+    };
+
+    let data = match kind {
+        TileLayerKind::Tiles | TileLayerKind::Game => TileData::Tiles(read_tiles(
+            layer_blob(file, item, 14)?,
+            version,
+            count,
+            item,
+        )?),
+        TileLayerKind::Front => TileData::Tiles(read_tiles(
+            layer_blob(file, item, 20)?,
+            version,
+            count,
+            item,
+        )?),
+        TileLayerKind::Tele => TileData::Tele(
+            sized_tiles(layer_blob(file, item, 18)?, 2, count, item)?
+                .map(|t| TeleTile {
+                    number: t[0],
+                    r#type: t[1],
+                })
+                .collect(),
+        ),
+        TileLayerKind::Speedup => TileData::Speedup(
+            sized_tiles(layer_blob(file, item, 19)?, 6, count, item)?
+                .map(|t| SpeedupTile {
+                    force: t[0],
+                    max_speed: t[1],
+                    r#type: t[2],
+                    angle: i16::from_le_bytes([t[4], t[5]]),
+                })
+                .collect(),
+        ),
+        TileLayerKind::Switch => TileData::Switch(
+            sized_tiles(layer_blob(file, item, 21)?, 4, count, item)?
+                .map(|t| SwitchTile {
+                    number: t[0],
+                    r#type: t[1],
+                    flags: t[2],
+                    delay: t[3],
+                })
+                .collect(),
+        ),
+        TileLayerKind::Tune => TileData::Tune(
+            sized_tiles(layer_blob(file, item, 22)?, 2, count, item)?
+                .map(|t| TuneTile {
+                    number: t[0],
+                    r#type: t[1],
+                })
+                .collect(),
+        ),
+    };
+
+    Ok(TileLayer {
+        name: if item.data.len() >= 18 {
+            ints_to_str(&item.data[15..18])
+        } else {
+            String::new()
+        },
+        kind,
+        detail,
+        width,
+        height,
+        color: [item.get(7), item.get(8), item.get(9), item.get(10)],
+        color_env: optional_index(item.get(11)),
+        image: optional_index(item.get(13)),
+        data,
+    })
+}
+
+/// Splits `raw` into `count` tiles of `size` bytes each.
+fn sized_tiles<'a>(
+    raw: &'a [u8],
+    size: usize,
+    count: usize,
+    item: &Item,
+) -> Result<std::slice::ChunksExact<'a, u8>> {
+    let len = count
+        .checked_mul(size)
+        .filter(|&len| len <= raw.len())
+        .ok_or_else(|| {
+            Error::Format(format!(
+                "layer {} has {} bytes of tile data, expected {count} tiles of {size} bytes",
+                item.id,
+                raw.len()
+            ))
+        })?;
+    Ok(raw[..len].chunks_exact(size))
+}
+
+/// Reads 4-byte tiles; tilemap version 4 and later run-length encode them via the skip byte.
+fn read_tiles(raw: &[u8], version: i32, count: usize, item: &Item) -> Result<Vec<Tile>> {
+    // The size comes from the header; check it against the blob before allocating.
+    let fits = if version >= 4 {
+        count <= raw.len() / 4 * 256
+    } else {
+        count.checked_mul(4) == Some(raw.len())
+    };
+    if !fits {
+        return Err(Error::Format(format!(
+            "layer {} claims {count} tiles, which {} bytes of version {version} tile data cannot hold",
+            item.id,
+            raw.len()
+        )));
+    }
+    let mut tiles = Vec::with_capacity(count);
+    for chunk in raw.chunks_exact(4) {
+        let tile = Tile {
+            index: chunk[0],
+            flags: chunk[1],
+        };
+        let repeat = if version >= 4 {
+            chunk[2] as usize + 1
+        } else {
+            1
+        };
+        tiles.extend(std::iter::repeat_n(tile, repeat));
+        if tiles.len() >= count {
+            break;
+        }
+    }
+    if tiles.len() < count {
+        return Err(Error::Format(format!(
+            "layer {} has {} tiles, expected {count}",
+            item.id,
+            tiles.len()
+        )));
+    }
+    tiles.truncate(count);
+    Ok(tiles)
+}
+
+fn read_quad_layer(file: &Datafile, item: &Item, detail: bool) -> Result<QuadLayer> {
+    let num = item.get(4).max(0) as usize;
+    let quads = if num == 0 {
+        Vec::new()
+    } else {
+        let raw = layer_blob(file, item, 5)?;
+        if raw.len() < num * 152 {
+            return Err(Error::Format(format!(
+                "quad layer {} has {} bytes, expected {}",
+                item.id,
+                raw.len(),
+                num * 152
+            )));
+        }
+        raw.chunks_exact(152)
+            .take(num)
+            .map(|q| {
+                let v: Vec<i32> = q
+                    .chunks_exact(4)
+                    .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                Quad {
+                    points: std::array::from_fn(|i| (v[i * 2], v[i * 2 + 1])),
+                    colors: std::array::from_fn(|i| std::array::from_fn(|c| v[10 + i * 4 + c])),
+                    tex_coords: std::array::from_fn(|i| (v[26 + i * 2], v[27 + i * 2])),
+                    pos_env: optional_index(v[34]),
+                    pos_env_offset: v[35],
+                    color_env: optional_index(v[36]),
+                    color_env_offset: v[37],
+                }
+            })
+            .collect()
+    };
+
+    Ok(QuadLayer {
+        name: if item.data.len() >= 10 {
+            ints_to_str(&item.data[7..10])
+        } else {
+            String::new()
+        },
+        detail,
+        image: optional_index(item.get(6)),
+        quads,
+    })
+}
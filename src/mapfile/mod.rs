@@ -0,0 +1,13 @@
+mod datafile;
+mod map;
+#[cfg(feature = "render")]
+mod render;
+mod stats;
+
+pub mod prelude {
+    pub use crate::mapfile::datafile::*;
+    pub use crate::mapfile::map::*;
+    #[cfg(feature = "render")]
+    pub use crate::mapfile::render::*;
+    pub use crate::mapfile::stats::*;
+}
@@ -0,0 +1,327 @@
+use crate::error::{Error, Result};
+use crate::mapfile::map::{MapFile, TileData, TileLayer, TileLayerKind};
+use std::path::Path;
+
+/// How a game or front layer tile is drawn in overview images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileClass {
+    Air,
+    Hookable,
+    Unhookable,
+    Freeze,
+    Unfreeze,
+    Death,
+    Start,
+    Finish,
+    Checkpoint,
+    Teleporter,
+    Speedup,
+    Switch,
+    /// Any other entity, drawn faintly so it stays visible.
+    Other,
+}
+
+impl TileClass {
+    /// Classifies a DDNet game/front layer tile index.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::mapfile::TileClass;
+    ///
+    /// assert_eq!(TileClass::from_index(1), TileClass::Hookable);
+    /// assert_eq!(TileClass::from_index(9), TileClass::Freeze);
+    /// assert_eq!(TileClass::from_index(33), TileClass::Start);
+    /// ```
+    pub fn from_index(index: u8) -> Self {
+        match index {
+            0 => TileClass::Air,
+            1 => TileClass::Hookable,
+            2 => TileClass::Death,
+            3 => TileClass::Unhookable,
+            9 | 12 => TileClass::Freeze,
+            11 | 13 => TileClass::Unfreeze,
+            10 | 14 | 15 | 26 | 27 | 29..=31 | 63 => TileClass::Teleporter,
+            33 => TileClass::Start,
+            34 => TileClass::Finish,
+            35..=59 | 64 | 65 => TileClass::Checkpoint,
+            28 => TileClass::Speedup,
+            _ => TileClass::Other,
+        }
+    }
+
+    /// RGBA colour used by [`render_overview`].
+    pub fn color(&self) -> [u8; 4] {
+        match self {
+            TileClass::Air => [0, 0, 0, 0],
+            TileClass::Hookable => [180, 180, 180, 255],
+            TileClass::Unhookable => [90, 90, 110, 255],
+            TileClass::Freeze => [40, 40, 60, 255],
+            TileClass::Unfreeze => [150, 200, 255, 255],
+            TileClass::Death => [220, 40, 40, 255],
+            TileClass::Start => [60, 200, 60, 255],
+            TileClass::Finish => [230, 200, 40, 255],
+            TileClass::Checkpoint => [200, 120, 40, 255],
+            TileClass::Teleporter => [160, 60, 220, 255],
+            TileClass::Speedup => [60, 200, 200, 255],
+            TileClass::Switch => [220, 100, 170, 255],
+            TileClass::Other => [120, 120, 120, 96],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderOptions {
+    /// Pixels per tile edge, reduced automatically to respect `max_dimension`.
+    ///
+    /// Maps larger than `max_dimension` tiles are drawn with several tiles per
+    /// pixel instead; each pixel then shows the first non-air tile of its block.
+    pub tile_size: u32,
+    /// Upper bound for the image width and height, in pixels.
+    pub max_dimension: u32,
+    pub background: [u8; 4],
+    /// Draw the front layer over the game layer.
+    pub front: bool,
+    /// Mark teleporter, speedup and switch tiles from their own layers.
+    pub special_layers: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            tile_size: 4,
+            max_dimension: 4096,
+            background: [30, 30, 40, 255],
+            front: true,
+            special_layers: true,
+        }
+    }
+}
+
+/// An RGBA8 image, row-major.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct RenderedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RenderedImage {
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        Some([
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ])
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| Error::Format(format!("png encoding failed: {e}")))?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|e| Error::Format(format!("png encoding failed: {e}")))?;
+        writer
+            .finish()
+            .map_err(|e| Error::Format(format!("png encoding failed: {e}")))?;
+        Ok(out)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+}
+
+/// Draws a top-down overview of the physics layers of a map.
+///
+/// Only the game layer is required; texture layers and quads are ignored, so
+/// the result looks like the ddnet.org entity previews rather than the
+/// in-game view.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::*;
+///
+/// let game = TileLayer {
+///     name: "Game".to_string(),
+///     kind: TileLayerKind::Game,
+///     detail: false,
+///     width: 2,
+///     height: 1,
+///     color: [255; 4],
+///     color_env: None,
+///     image: None,
+///     data: TileData::Tiles(vec![Tile { index: 1, flags: 0 }, Tile { index: 9, flags: 0 }]),
+/// };
+/// let map = MapFile {
+///     groups: vec![Group { layers: vec![Layer::Tiles(game)], ..Default::default() }],
+///     ..Default::default()
+/// };
+///
+/// let options = RenderOptions { tile_size: 2, ..Default::default() };
+/// let image = render_overview(&map, &options).unwrap();
+/// assert_eq!((image.width, image.height), (4, 2));
+/// assert_eq!(image.pixel(0, 0), Some(TileClass::Hookable.color()));
+/// assert_eq!(image.pixel(3, 1), Some(TileClass::Freeze.color()));
+/// assert!(image.to_png().unwrap().starts_with(b"\x89PNG"));
+///
+/// // Wider than `max_dimension`: both tiles share one pixel.
+/// let options = RenderOptions { max_dimension: 1, ..Default::default() };
+/// let image = render_overview(&map, &options).unwrap();
+/// assert_eq!((image.width, image.height), (1, 1));
+/// assert_eq!(image.pixel(0, 0), Some(TileClass::Hookable.color()));
+///
+/// // Tiles that do not cover the layer are rejected.
+/// let mut short = map.clone();
+/// if let Layer::Tiles(game) = &mut short.groups[0].layers[0] {
+///     game.width = 3;
+/// }
+/// let err = render_overview(&short, &options).unwrap_err();
+/// assert!(matches!(err, ddapi_rs::prelude::Error::Format(_)));
+/// ```
+pub fn render_overview(map: &MapFile, options: &RenderOptions) -> Result<RenderedImage> {
+    let game = map
+        .game_layer()
+        .ok_or_else(|| Error::Format("map has no game layer".to_string()))?;
+    let (width, height) = (game.width, game.height);
+    if width == 0 || height == 0 {
+        return Err(Error::Format("game layer is empty".to_string()));
+    }
+    // The layer fields are public, so they may not match each other.
+    let tiles = game
+        .tiles()
+        .ok_or_else(|| Error::Format("game layer has no plain tiles".to_string()))?;
+    if tiles.len() as u64 != u64::from(width) * u64::from(height) {
+        return Err(Error::Format(format!(
+            "game layer has {} tiles, expected {width}x{height}",
+            tiles.len()
+        )));
+    }
+
+    if options.max_dimension == 0 {
+        return Err(Error::Format(
+            "max_dimension must be at least 1".to_string(),
+        ));
+    }
+
+    // `step` tiles per pixel edge once even one pixel per tile is too large.
+    let longest = width.max(height);
+    let (tile_size, step) = if longest <= options.max_dimension {
+        let fit = options.max_dimension / longest;
+        (options.tile_size.min(fit).max(1), 1)
+    } else {
+        (1, longest.div_ceil(options.max_dimension))
+    };
+
+    let mut classes: Vec<TileClass> = tiles
+        .iter()
+        .map(|t| TileClass::from_index(t.index))
+        .collect();
+
+    let same_size = |layer: &TileLayer| layer.width == width && layer.height == height;
+
+    if options.front {
+        if let Some(front) = map
+            .physics_layer(TileLayerKind::Front)
+            .filter(|l| same_size(l))
+        {
+            overlay(&mut classes, front.tiles().unwrap_or_default(), |t| {
+                TileClass::from_index(t.index)
+            });
+        }
+    }
+
+    if options.special_layers {
+        for layer in map.tile_layers().filter(|l| same_size(l)) {
+            match &layer.data {
+                TileData::Tele(tiles) => overlay(&mut classes, tiles, |t| {
+                    if t.r#type == 0 {
+                        TileClass::Air
+                    } else {
+                        TileClass::Teleporter
+                    }
+                }),
+                TileData::Speedup(tiles) => overlay(&mut classes, tiles, |t| {
+                    if t.r#type == 0 {
+                        TileClass::Air
+                    } else {
+                        TileClass::Speedup
+                    }
+                }),
+                TileData::Switch(tiles) => overlay(&mut classes, tiles, |t| {
+                    if t.r#type == 0 {
+                        TileClass::Air
+                    } else {
+                        TileClass::Switch
+                    }
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    let image_width = width.div_ceil(step) * tile_size;
+    let image_height = height.div_ceil(step) * tile_size;
+    let mut pixels = Vec::with_capacity(image_width as usize * image_height as usize * 4);
+    for y in 0..image_height {
+        let top = y / tile_size * step;
+        for x in 0..image_width {
+            let left = x / tile_size * step;
+            let class = block_class(&classes, (width, height), (left, top), step);
+            pixels.extend_from_slice(&blend(options.background, class.color()));
+        }
+    }
+
+    Ok(RenderedImage {
+        width: image_width,
+        height: image_height,
+        pixels,
+    })
+}
+
+/// The first non-air class of the `step`×`step` block of tiles starting at `(left, top)`.
+fn block_class(
+    classes: &[TileClass],
+    (width, height): (u32, u32),
+    (left, top): (u32, u32),
+    step: u32,
+) -> TileClass {
+    (top..(top + step).min(height))
+        .flat_map(|y| {
+            (left..(left + step).min(width)).map(move |x| y as usize * width as usize + x as usize)
+        })
+        .map(|i| classes[i])
+        .find(|class| *class != TileClass::Air)
+        .unwrap_or(TileClass::Air)
+}
+
+/// Replaces classes with the non-air classes of another layer.
+fn overlay<T>(classes: &mut [TileClass], tiles: &[T], classify: impl Fn(&T) -> TileClass) {
+    for (class, tile) in classes.iter_mut().zip(tiles) {
+        let over = classify(tile);
+        if over != TileClass::Air {
+            *class = over;
+        }
+    }
+}
+
+fn blend(background: [u8; 4], color: [u8; 4]) -> [u8; 4] {
+    let alpha = color[3] as u32;
+    let mix = |bg: u8, fg: u8| ((fg as u32 * alpha + bg as u32 * (255 - alpha)) / 255) as u8;
+    [
+        mix(background[0], color[0]),
+        mix(background[1], color[1]),
+        mix(background[2], color[2]),
+        background[3].max(color[3]),
+    ]
+}
@@ -0,0 +1,407 @@
+use crate::mapfile::map::{MapFile, TileData, TileLayer, TileLayerKind};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+const TILE_SOLID: u8 = 1;
+const TILE_DEATH: u8 = 2;
+const TILE_NOHOOK: u8 = 3;
+const TILE_TELEINEVIL: u8 = 10;
+const TILE_TELEINWEAPON: u8 = 14;
+const TILE_TELEINHOOK: u8 = 15;
+const TILE_TELEIN: u8 = 26;
+const TILE_TELEOUT: u8 = 27;
+const TILE_TELECHECK: u8 = 29;
+const TILE_TELECHECKOUT: u8 = 30;
+const TILE_TELECHECKIN: u8 = 31;
+const TILE_TIME_CHECKPOINT_FIRST: u8 = 35;
+const TILE_TIME_CHECKPOINT_LAST: u8 = 59;
+const TILE_TELECHECKINEVIL: u8 = 63;
+const TILE_TUNE: u8 = 68;
+const ENTITY_SPAWN: u8 = 192;
+const ENTITY_SPAWN_BLUE: u8 = 194;
+
+/// DDNet name of a tile index, without the `TILE_`/`ENTITY_` prefix.
+///
+/// These are the names ddnet.org lists in `Map::tiles`. Tele, speedup,
+/// switch and tune layers store the same numbering in their `type` byte.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::tile_name;
+///
+/// assert_eq!(tile_name(9), Some("FREEZE"));
+/// assert_eq!(tile_name(192), Some("SPAWN"));
+/// assert_eq!(tile_name(0), None);
+/// ```
+pub fn tile_name(index: u8) -> Option<&'static str> {
+    Some(match index {
+        1 => "SOLID",
+        2 => "DEATH",
+        3 => "NOHOOK",
+        4 => "NOLASER",
+        5 => "THROUGH_CUT",
+        6 => "THROUGH",
+        7 => "JUMP",
+        9 => "FREEZE",
+        10 => "TELEINEVIL",
+        11 => "UNFREEZE",
+        12 => "DFREEZE",
+        13 => "DUNFREEZE",
+        14 => "TELEINWEAPON",
+        15 => "TELEINHOOK",
+        16 => "WALLJUMP",
+        17 => "EHOOK_START",
+        18 => "EHOOK_END",
+        19 => "HIT_START",
+        20 => "HIT_END",
+        21 => "SOLO_START",
+        22 => "SOLO_END",
+        23 => "SWITCHTIMEDCLOSE",
+        24 => "SWITCHOPEN",
+        25 => "SWITCHCLOSE",
+        26 => "TELEIN",
+        27 => "TELEOUT",
+        28 => "BOOST",
+        29 => "TELECHECK",
+        30 => "TELECHECKOUT",
+        31 => "TELECHECKIN",
+        32 => "REFILL_JUMPS",
+        33 => "BEGIN",
+        34 => "END",
+        35..=59 => "TIME_CHECKPOINT",
+        60 => "STOP",
+        61 => "STOPS",
+        62 => "STOPA",
+        63 => "TELECHECKINEVIL",
+        64 => "CP",
+        65 => "CP_F",
+        66 => "THROUGH_ALL",
+        67 => "THROUGH_DIR",
+        68 => "TUNE",
+        71 => "OLDLASER",
+        72 => "NPC",
+        73 => "EHOOK",
+        74 => "NOHIT",
+        75 => "NPH",
+        76 => "UNLOCK_TEAM",
+        79 => "ADD_TIME",
+        88 => "NPC_END",
+        89 => "SUPER_END",
+        90 => "JETPACK_END",
+        91 => "NPH_END",
+        95 => "SUBTRACT_TIME",
+        96 => "TELE_GUN_ENABLE",
+        97 => "TELE_GUN_DISABLE",
+        98 => "ALLOW_TELE_GUN",
+        99 => "ALLOW_BLUE_TELE_GUN",
+        104 => "NPC_START",
+        105 => "SUPER_START",
+        106 => "JETPACK_START",
+        107 => "NPH_START",
+        112 => "TELE_GRENADE_ENABLE",
+        113 => "TELE_GRENADE_DISABLE",
+        128 => "TELE_LASER_ENABLE",
+        129 => "TELE_LASER_DISABLE",
+        192 => "SPAWN",
+        193 => "SPAWN_RED",
+        194 => "SPAWN_BLUE",
+        195 => "FLAGSTAND_RED",
+        196 => "FLAGSTAND_BLUE",
+        197 => "ARMOR_1",
+        198 => "HEALTH_1",
+        199 => "WEAPON_SHOTGUN",
+        200 => "WEAPON_GRENADE",
+        201 => "POWERUP_NINJA",
+        202 => "WEAPON_LASER",
+        _ => return None,
+    })
+}
+
+/// Inputs and outputs of one teleporter number.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Teleporter {
+    pub number: u8,
+    /// `TELEIN`, `TELEINEVIL`, `TELEINWEAPON` and `TELEINHOOK` tiles.
+    pub inputs: usize,
+    pub outputs: usize,
+    /// `TELECHECK` tiles, which remember this number as the checkpoint teleporter.
+    pub checkpoints: usize,
+    pub checkpoint_inputs: usize,
+    pub checkpoint_outputs: usize,
+}
+
+impl Teleporter {
+    /// Has at least one way in and one way out.
+    pub fn is_paired(&self) -> bool {
+        self.inputs > 0 && self.outputs > 0
+    }
+}
+
+/// Tile statistics of a map's physics layers.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::mapfile::*;
+///
+/// let tiles = |indices: &[u8]| {
+///     TileData::Tiles(indices.iter().map(|&index| Tile { index, flags: 0 }).collect())
+/// };
+/// let layer = |kind, data| TileLayer {
+///     name: String::new(),
+///     kind,
+///     detail: false,
+///     width: 4,
+///     height: 1,
+///     color: [255; 4],
+///     color_env: None,
+///     image: None,
+///     data,
+/// };
+/// let tele = TileData::Tele(vec![
+///     TeleTile::default(),
+///     TeleTile::default(),
+///     TeleTile { number: 1, r#type: 26 },
+///     TeleTile { number: 1, r#type: 27 },
+/// ]);
+/// let map = MapFile {
+///     groups: vec![Group {
+///         layers: vec![
+///             Layer::Tiles(layer(TileLayerKind::Game, tiles(&[192, 0, 0, 35]))),
+///             Layer::Tiles(layer(TileLayerKind::Tele, tele)),
+///         ],
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// let stats = TileStats::new(&map);
+/// assert_eq!(stats.count(TileLayerKind::Game, 0), 2);
+/// assert_eq!(stats.checkpoints.iter().copied().collect::<Vec<_>>(), vec![0]);
+/// assert!(stats.teleporters[0].is_paired());
+/// assert_eq!(stats.playable_area, 4);
+/// assert!(stats.tile_names().contains("TELEIN"));
+///
+/// // Behind a wall, only a teleporter entered by walking reaches the last tile.
+/// let area_through = |r#type| {
+///     let tele = TileData::Tele(vec![
+///         TeleTile::default(),
+///         TeleTile { number: 1, r#type },
+///         TeleTile::default(),
+///         TeleTile { number: 1, r#type: 27 },
+///     ]);
+///     let map = MapFile {
+///         groups: vec![Group {
+///             layers: vec![
+///                 Layer::Tiles(layer(TileLayerKind::Game, tiles(&[192, 0, 1, 0]))),
+///                 Layer::Tiles(layer(TileLayerKind::Tele, tele)),
+///             ],
+///             ..Default::default()
+///         }],
+///         ..Default::default()
+///     };
+///     TileStats::new(&map).playable_area
+/// };
+/// assert_eq!(area_through(26), 3);
+/// assert_eq!(area_through(14), 2);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct TileStats {
+    /// Tile index (or `type` for special layers) counts per physics layer.
+    pub counts: BTreeMap<TileLayerKind, BTreeMap<u8, usize>>,
+    /// Race checkpoint numbers (`0..=24`) present in the game or front layer.
+    pub checkpoints: BTreeSet<u8>,
+    /// Teleporters by number, ascending.
+    pub teleporters: Vec<Teleporter>,
+    pub switch_numbers: BTreeSet<u8>,
+    pub tune_zones: BTreeSet<u8>,
+    /// Tiles reachable from a spawn without passing solid or death tiles,
+    /// following the teleporters a tee enters by walking in (`TELEIN`,
+    /// `TELEINEVIL`). Zero when the map has no spawn.
+    pub playable_area: usize,
+}
+
+impl TileStats {
+    pub fn new(map: &MapFile) -> Self {
+        let mut stats = TileStats::default();
+        let mut teleporters: BTreeMap<u8, Teleporter> = BTreeMap::new();
+
+        for layer in map.tile_layers().filter(|l| l.kind != TileLayerKind::Tiles) {
+            let counts = stats.counts.entry(layer.kind).or_default();
+            match &layer.data {
+                TileData::Tiles(tiles) => {
+                    for tile in tiles {
+                        *counts.entry(tile.index).or_default() += 1;
+                        if (TILE_TIME_CHECKPOINT_FIRST..=TILE_TIME_CHECKPOINT_LAST)
+                            .contains(&tile.index)
+                        {
+                            stats
+                                .checkpoints
+                                .insert(tile.index - TILE_TIME_CHECKPOINT_FIRST);
+                        }
+                    }
+                }
+                TileData::Tele(tiles) => {
+                    for tile in tiles {
+                        *counts.entry(tile.r#type).or_default() += 1;
+                        if tile.r#type == 0 {
+                            continue;
+                        }
+                        let entry = teleporters.entry(tile.number).or_insert(Teleporter {
+                            number: tile.number,
+                            ..Default::default()
+                        });
+                        match tile.r#type {
+                            TILE_TELEIN | TILE_TELEINEVIL | TILE_TELEINWEAPON | TILE_TELEINHOOK => {
+                                entry.inputs += 1
+                            }
+                            TILE_TELEOUT => entry.outputs += 1,
+                            TILE_TELECHECK => entry.checkpoints += 1,
+                            TILE_TELECHECKIN | TILE_TELECHECKINEVIL => entry.checkpoint_inputs += 1,
+                            TILE_TELECHECKOUT => entry.checkpoint_outputs += 1,
+                            _ => {}
+                        }
+                    }
+                }
+                TileData::Speedup(tiles) => {
+                    for tile in tiles {
+                        *counts.entry(tile.r#type).or_default() += 1;
+                    }
+                }
+                TileData::Switch(tiles) => {
+                    for tile in tiles {
+                        *counts.entry(tile.r#type).or_default() += 1;
+                        if tile.r#type != 0 && tile.number != 0 {
+                            stats.switch_numbers.insert(tile.number);
+                        }
+                    }
+                }
+                TileData::Tune(tiles) => {
+                    for tile in tiles {
+                        *counts.entry(tile.r#type).or_default() += 1;
+                        if tile.r#type == TILE_TUNE {
+                            stats.tune_zones.insert(tile.number);
+                        }
+                    }
+                }
+            }
+        }
+
+        stats.teleporters = teleporters.into_values().collect();
+        stats.playable_area = playable_area(map);
+        stats
+    }
+
+    /// How often `index` occurs in the given physics layer.
+    pub fn count(&self, kind: TileLayerKind, index: u8) -> usize {
+        self.counts
+            .get(&kind)
+            .and_then(|c| c.get(&index))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Names of every named tile present in any physics layer, like ddnet.org's `Map::tiles`.
+    pub fn tile_names(&self) -> BTreeSet<&'static str> {
+        self.counts
+            .iter()
+            .flat_map(|(kind, counts)| counts.keys().map(move |index| (*kind, *index)))
+            .filter(|(_, index)| *index != 0)
+            .filter_map(|(kind, index)| match kind {
+                TileLayerKind::Game | TileLayerKind::Front | TileLayerKind::Tele => {
+                    tile_name(index)
+                }
+                TileLayerKind::Speedup => Some("BOOST"),
+                TileLayerKind::Switch => tile_name(index).or(Some("SWITCH")),
+                TileLayerKind::Tune => Some("TUNE"),
+                TileLayerKind::Tiles => None,
+            })
+            .collect()
+    }
+}
+
+/// Flood fill over the game (and front) layer starting at every spawn.
+fn playable_area(map: &MapFile) -> usize {
+    let Some(game) = map.game_layer() else {
+        return 0;
+    };
+    let (width, height) = (game.width as usize, game.height as usize);
+    let same_size = |l: &&TileLayer| l.width == game.width && l.height == game.height;
+    let game_tiles = game.tiles().unwrap_or_default();
+    let front_tiles = map
+        .physics_layer(TileLayerKind::Front)
+        .filter(same_size)
+        .and_then(TileLayer::tiles);
+    let tele_tiles = map
+        .physics_layer(TileLayerKind::Tele)
+        .filter(same_size)
+        .and_then(|l| match &l.data {
+            TileData::Tele(tiles) => Some(tiles.as_slice()),
+            _ => None,
+        });
+
+    let index_at = |i: usize| -> (u8, u8) {
+        (
+            game_tiles.get(i).map_or(0, |t| t.index),
+            front_tiles.and_then(|f| f.get(i)).map_or(0, |t| t.index),
+        )
+    };
+    let blocked = |i: usize| {
+        let (g, f) = index_at(i);
+        [g, f]
+            .iter()
+            .any(|&t| matches!(t, TILE_SOLID | TILE_NOHOOK | TILE_DEATH))
+    };
+
+    let mut outputs: BTreeMap<u8, Vec<usize>> = BTreeMap::new();
+    if let Some(tele) = tele_tiles {
+        for (i, tile) in tele.iter().enumerate() {
+            if tile.r#type == TILE_TELEOUT {
+                outputs.entry(tile.number).or_default().push(i);
+            }
+        }
+    }
+
+    let mut visited = vec![false; width * height];
+    let mut queue: VecDeque<usize> = (0..width * height)
+        .filter(|&i| {
+            let (g, f) = index_at(i);
+            (ENTITY_SPAWN..=ENTITY_SPAWN_BLUE).contains(&g)
+                || (ENTITY_SPAWN..=ENTITY_SPAWN_BLUE).contains(&f)
+        })
+        .collect();
+    for &i in &queue {
+        visited[i] = true;
+    }
+
+    let mut area = 0;
+    while let Some(i) = queue.pop_front() {
+        area += 1;
+        let (x, y) = (i % width, i / width);
+        let mut next = Vec::with_capacity(4);
+        if x > 0 {
+            next.push(i - 1);
+        }
+        if x + 1 < width {
+            next.push(i + 1);
+        }
+        if y > 0 {
+            next.push(i - width);
+        }
+        if y + 1 < height {
+            next.push(i + width);
+        }
+        if let Some(tile) = tele_tiles.and_then(|t| t.get(i)) {
+            // Weapon and hook teleporters only move what hits them, and
+            // checkpoint teleporters depend on the last checkpoint touched.
+            if matches!(tile.r#type, TILE_TELEIN | TILE_TELEINEVIL) {
+                next.extend(outputs.get(&tile.number).into_iter().flatten());
+            }
+        }
+        for n in next {
+            if !visited[n] && !blocked(n) {
+                visited[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+    area
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// DDNet map category, as used in player type breakdowns and map releases.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::Category;
+///
+/// assert_eq!(Category::DDmaXEasy.as_str(), "DDmaX.Easy");
+/// assert_eq!("Insane".parse::<Category>(), Ok(Category::Insane));
+/// assert!("Easy".parse::<Category>().is_err());
+/// assert_eq!(Category::ALL.len(), 13);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Category {
+    Novice,
+    Moderate,
+    Brutal,
+    Insane,
+    Dummy,
+    #[serde(rename = "DDmaX.Easy")]
+    DDmaXEasy,
+    #[serde(rename = "DDmaX.Next")]
+    DDmaXNext,
+    #[serde(rename = "DDmaX.Pro")]
+    DDmaXPro,
+    #[serde(rename = "DDmaX.Nut")]
+    DDmaXNut,
+    Oldschool,
+    Solo,
+    Race,
+    Fun,
+}
+
+impl Category {
+    /// Every category, in the order ddnet.org lists them.
+    pub const ALL: [Category; 13] = [
+        Category::Novice,
+        Category::Moderate,
+        Category::Brutal,
+        Category::Insane,
+        Category::Dummy,
+        Category::DDmaXEasy,
+        Category::DDmaXNext,
+        Category::DDmaXPro,
+        Category::DDmaXNut,
+        Category::Oldschool,
+        Category::Solo,
+        Category::Race,
+        Category::Fun,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Category::Novice => "Novice",
+            Category::Moderate => "Moderate",
+            Category::Brutal => "Brutal",
+            Category::Insane => "Insane",
+            Category::Dummy => "Dummy",
+            Category::DDmaXEasy => "DDmaX.Easy",
+            Category::DDmaXNext => "DDmaX.Next",
+            Category::DDmaXPro => "DDmaX.Pro",
+            Category::DDmaXNut => "DDmaX.Nut",
+            Category::Oldschool => "Oldschool",
+            Category::Solo => "Solo",
+            Category::Race => "Race",
+            Category::Fun => "Fun",
+        }
+    }
+}
+
+/// Returned when parsing a string that is not a [`Category`] name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseCategoryError(pub String);
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown category: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseCategoryError {}
+
+impl FromStr for Category {
+    type Err = ParseCategoryError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Category::ALL
+            .into_iter()
+            .find(|c| c.as_str() == value)
+            .ok_or_else(|| ParseCategoryError(value.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Category {
+    type Error = ParseCategoryError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -0,0 +1,179 @@
+use crate::scheme::ddnet::player::Player;
+use crate::scheme::Category;
+use std::collections::HashMap;
+
+/// A map both players have finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedMap {
+    pub category: Category,
+    pub name: String,
+    pub time_a: Option<f64>,
+    pub time_b: Option<f64>,
+}
+
+impl SharedMap {
+    /// `time_a - time_b` in seconds; negative when player A is faster.
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.time_a? - self.time_b?)
+    }
+}
+
+/// A map only one of the two players has finished.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExclusiveMap {
+    pub category: Category,
+    pub name: String,
+    pub time: Option<f64>,
+}
+
+/// Points and ranks of both players in one category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryComparison {
+    pub category: Category,
+    pub points_a: Option<u64>,
+    pub points_b: Option<u64>,
+    pub rank_a: Option<u64>,
+    pub rank_b: Option<u64>,
+}
+
+impl CategoryComparison {
+    /// `rank_a - rank_b`; negative when player A is ranked higher.
+    pub fn rank_delta(&self) -> Option<i64> {
+        Some(self.rank_a? as i64 - self.rank_b? as i64)
+    }
+}
+
+/// Head-to-head comparison of two ddnet players.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::*;
+///
+/// let finished = |time: f64| DDMap { finishes: 1, time: Some(time), ..Default::default() };
+///
+/// let mut a = Player { player: "A".to_string(), ..Default::default() };
+/// a.types.novice.maps.insert("Tutorial".to_string(), finished(60.0));
+/// a.types.novice.maps.insert("Multeasymap".to_string(), finished(300.0));
+/// a.favorite_partners.push(FavoritePartner { name: "B".to_string(), finishes: 4 });
+///
+/// let mut b = Player { player: "B".to_string(), ..Default::default() };
+/// b.types.novice.maps.insert("Tutorial".to_string(), finished(75.5));
+///
+/// let cmp = a.compare(&b);
+/// assert_eq!(cmp.shared.len(), 1);
+/// assert_eq!(cmp.shared[0].delta(), Some(-15.5));
+/// assert_eq!(cmp.only_a[0].name, "Multeasymap");
+/// assert!(cmp.only_b.is_empty());
+/// assert!(cmp.a_partners_b && !cmp.b_partners_a);
+/// assert_eq!(cmp.faster_count(), (1, 0));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub player_a: String,
+    pub player_b: String,
+    /// Maps finished by both, sorted by name.
+    pub shared: Vec<SharedMap>,
+    /// Maps finished only by player A, sorted by name.
+    pub only_a: Vec<ExclusiveMap>,
+    /// Maps finished only by player B, sorted by name.
+    pub only_b: Vec<ExclusiveMap>,
+    pub categories: Vec<CategoryComparison>,
+    /// Player B is listed in player A's `favorite_partners`.
+    pub a_partners_b: bool,
+    /// Player A is listed in player B's `favorite_partners`.
+    pub b_partners_a: bool,
+}
+
+impl Comparison {
+    pub fn new(a: &Player, b: &Player) -> Self {
+        let finished = |player: &Player| -> HashMap<String, (Category, Option<f64>)> {
+            player
+                .types
+                .finished_maps()
+                .map(|(category, name, map)| (name.to_string(), (category, map.time)))
+                .collect()
+        };
+        let maps_a = finished(a);
+        let mut maps_b = finished(b);
+
+        let mut shared = Vec::new();
+        let mut only_a = Vec::new();
+        for (name, (category, time_a)) in maps_a {
+            match maps_b.remove(&name) {
+                Some((_, time_b)) => shared.push(SharedMap {
+                    category,
+                    name,
+                    time_a,
+                    time_b,
+                }),
+                None => only_a.push(ExclusiveMap {
+                    category,
+                    name,
+                    time: time_a,
+                }),
+            }
+        }
+        let mut only_b: Vec<ExclusiveMap> = maps_b
+            .into_iter()
+            .map(|(name, (category, time))| ExclusiveMap {
+                category,
+                name,
+                time,
+            })
+            .collect();
+
+        shared.sort_by(|x, y| x.name.cmp(&y.name));
+        only_a.sort_by(|x, y| x.name.cmp(&y.name));
+        only_b.sort_by(|x, y| x.name.cmp(&y.name));
+
+        let categories = a
+            .types
+            .iter()
+            .zip(b.types.iter())
+            .map(|((category, ta), (_, tb))| CategoryComparison {
+                category,
+                points_a: ta.points.points,
+                points_b: tb.points.points,
+                rank_a: ta.rank.as_ref().and_then(|r| r.rank),
+                rank_b: tb.rank.as_ref().and_then(|r| r.rank),
+            })
+            .collect();
+
+        let partners =
+            |player: &Player, other: &str| player.favorite_partners.iter().any(|p| p.name == other);
+
+        Self {
+            player_a: a.player.clone(),
+            player_b: b.player.clone(),
+            shared,
+            only_a,
+            only_b,
+            categories,
+            a_partners_b: partners(a, &b.player),
+            b_partners_a: partners(b, &a.player),
+        }
+    }
+
+    /// Number of shared maps where player A, respectively player B, has the better time.
+    pub fn faster_count(&self) -> (usize, usize) {
+        self.shared
+            .iter()
+            .filter_map(SharedMap::delta)
+            .fold((0, 0), |(a, b), delta| {
+                if delta < 0.0 {
+                    (a + 1, b)
+                } else if delta > 0.0 {
+                    (a, b + 1)
+                } else {
+                    (a, b)
+                }
+            })
+    }
+}
+
+impl Player {
+    /// Compares this player (A) against `other` (B), see [`Comparison`].
+    pub fn compare(&self, other: &Player) -> Comparison {
+        Comparison::new(self, other)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::scheme::ddnet::player::Player;
+use crate::scheme::ddnet::releases_maps::ReleasesMaps;
+use crate::scheme::Category;
+use std::collections::{BTreeMap, HashSet};
+
+/// Finished/total counters for a set of released maps.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompletionStats {
+    pub maps_finished: u64,
+    pub maps_total: u64,
+    pub points_earned: u64,
+    pub points_available: u64,
+}
+
+impl CompletionStats {
+    /// Share of maps finished, from `0.0` to `1.0`.
+    pub fn maps_ratio(&self) -> f64 {
+        ratio(self.maps_finished, self.maps_total)
+    }
+
+    /// Share of map points earned, from `0.0` to `1.0`.
+    pub fn points_ratio(&self) -> f64 {
+        ratio(self.points_earned, self.points_available)
+    }
+
+    fn add(&mut self, map: &ReleasesMaps, finished: bool) {
+        self.maps_total += 1;
+        self.points_available += map.points as u64;
+        if finished {
+            self.maps_finished += 1;
+            self.points_earned += map.points as u64;
+        }
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// A player's progress through the ddnet.org map releases.
+///
+/// Maps are matched by name against every category of [`Player::types`], so a
+/// map that changed category after the player finished it still counts.
+/// Releases whose type is not a known [`Category`] are ignored.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::Category;
+/// use ddapi_rs::prelude::ddnet::*;
+///
+/// let release = |name: &str, r#type: &str, points: u8, difficulty: u8| ReleasesMaps {
+///     name: name.to_string(),
+///     r#type: r#type.to_string(),
+///     points,
+///     difficulty,
+///     ..Default::default()
+/// };
+/// let releases = vec![
+///     release("Tutorial", "Novice", 1, 0),
+///     release("Multeasymap", "Novice", 2, 1),
+///     release("Kobra 4", "Moderate", 15, 3),
+/// ];
+///
+/// let mut player = Player::default();
+/// player.types.novice.maps.insert(
+///     "Tutorial".to_string(),
+///     DDMap { finishes: 1, ..Default::default() },
+/// );
+///
+/// let completion = player.completion(&releases);
+/// let novice = completion.category(Category::Novice).unwrap();
+/// assert_eq!((novice.maps_finished, novice.maps_total), (1, 2));
+/// assert_eq!(completion.total.points_earned, 1);
+/// assert_eq!(completion.total.points_available, 18);
+/// assert_eq!(completion.easiest_unfinished(1)[0].name, "Multeasymap");
+/// assert_eq!(completion.unfinished_by_points()[0].name, "Kobra 4");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub total: CompletionStats,
+    /// Per-category stats in [`Category::ALL`] order, only for categories with releases.
+    pub categories: Vec<(Category, CompletionStats)>,
+    /// Released maps the player has not finished, in release list order.
+    pub unfinished: Vec<ReleasesMaps>,
+}
+
+impl Completion {
+    pub fn new(player: &Player, releases: &[ReleasesMaps]) -> Self {
+        let finished: HashSet<&str> = player
+            .types
+            .finished_maps()
+            .map(|(_, name, _)| name)
+            .collect();
+
+        let mut total = CompletionStats::default();
+        let mut per_category: BTreeMap<Category, CompletionStats> = BTreeMap::new();
+        let mut unfinished = Vec::new();
+
+        for map in releases {
+            let Some(category) = map.category() else {
+                continue;
+            };
+
+            let is_finished = finished.contains(map.name.as_str());
+            total.add(map, is_finished);
+            per_category
+                .entry(category)
+                .or_default()
+                .add(map, is_finished);
+            if !is_finished {
+                unfinished.push(map.clone());
+            }
+        }
+
+        // `Category`'s order is the `Category::ALL` order.
+        let categories = per_category.into_iter().collect();
+
+        Self {
+            total,
+            categories,
+            unfinished,
+        }
+    }
+
+    pub fn category(&self, category: Category) -> Option<&CompletionStats> {
+        self.categories
+            .iter()
+            .find(|(c, _)| *c == category)
+            .map(|(_, stats)| stats)
+    }
+
+    /// Unfinished maps, most points first.
+    pub fn unfinished_by_points(&self) -> Vec<&ReleasesMaps> {
+        let mut maps: Vec<&ReleasesMaps> = self.unfinished.iter().collect();
+        maps.sort_by(|a, b| b.points.cmp(&a.points).then_with(|| a.name.cmp(&b.name)));
+        maps
+    }
+
+    /// Unfinished maps, fewest difficulty stars first.
+    pub fn unfinished_by_difficulty(&self) -> Vec<&ReleasesMaps> {
+        let mut maps: Vec<&ReleasesMaps> = self.unfinished.iter().collect();
+        maps.sort_by(|a, b| {
+            a.difficulty
+                .cmp(&b.difficulty)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        maps
+    }
+
+    /// Up to `limit` unfinished maps to try next: lowest difficulty, then lowest points.
+    pub fn easiest_unfinished(&self, limit: usize) -> Vec<&ReleasesMaps> {
+        let mut maps: Vec<&ReleasesMaps> = self.unfinished.iter().collect();
+        maps.sort_by(|a, b| {
+            (a.difficulty, a.points)
+                .cmp(&(b.difficulty, b.points))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        maps.truncate(limit);
+        maps
+    }
+}
+
+impl Player {
+    /// Computes completion against a release list, see [`Completion`].
+    pub fn completion(&self, releases: &[ReleasesMaps]) -> Completion {
+        Completion::new(self, releases)
+    }
+}
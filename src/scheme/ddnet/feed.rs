@@ -0,0 +1,408 @@
+use crate::error::{Error, Result};
+use crate::prelude::{LenientParse, RaceTime};
+use crate::scheme::{Category, DDNET_BASE_URL};
+use chrono::{DateTime, NaiveDateTime};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// An RSS 2.0 or Atom feed.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Feed {
+    pub title: String,
+    /// Newest first, in document order.
+    pub entries: Vec<FeedEntry>,
+}
+
+/// One `<item>` (RSS) or `<entry>` (Atom).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    /// `<guid>` / `<id>`, falling back to the link and then the title.
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    /// `<description>`, `<summary>` or `<content>`, as sent (may contain HTML).
+    pub summary: String,
+    /// `<pubDate>`, `<published>` or `<updated>`, in UTC.
+    pub published: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Link,
+    Summary,
+    Published,
+    Updated,
+    Id,
+}
+
+impl Field {
+    fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"title" => Some(Field::Title),
+            b"link" => Some(Field::Link),
+            b"description" | b"summary" | b"content" => Some(Field::Summary),
+            b"pubDate" | b"published" => Some(Field::Published),
+            b"updated" => Some(Field::Updated),
+            b"guid" | b"id" => Some(Field::Id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct EntryBuilder {
+    entry: FeedEntry,
+    updated: Option<NaiveDateTime>,
+}
+
+impl EntryBuilder {
+    fn set(&mut self, field: Field, text: String) {
+        let entry = &mut self.entry;
+        match field {
+            Field::Title => entry.title = text,
+            Field::Link if entry.link.is_empty() => entry.link = text,
+            Field::Summary if entry.summary.is_empty() => entry.summary = text,
+            Field::Published => entry.published = parse_feed_date(&text),
+            Field::Updated => self.updated = parse_feed_date(&text),
+            Field::Id => entry.id = text,
+            Field::Link | Field::Summary => {}
+        }
+    }
+
+    fn build(self) -> FeedEntry {
+        let mut entry = self.entry;
+        entry.published = entry.published.or(self.updated);
+        if entry.id.is_empty() {
+            entry.id = if entry.link.is_empty() {
+                entry.title.clone()
+            } else {
+                entry.link.clone()
+            };
+        }
+        entry
+    }
+}
+
+fn parse_feed_date(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    DateTime::parse_from_rfc2822(value)
+        .map(|date| date.naive_utc())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_lenient(value))
+}
+
+/// `href` of an Atom `<link>`, skipping links that are not the entry itself.
+fn atom_link(start: &BytesStart) -> Option<String> {
+    let rel = start.try_get_attribute("rel").ok().flatten();
+    if rel.is_some_and(|rel| rel.value.as_ref() != b"alternate") {
+        return None;
+    }
+    let href = start.try_get_attribute("href").ok().flatten()?;
+    href.unescape_value().ok().map(|href| href.into_owned())
+}
+
+fn xml_error(e: impl std::fmt::Display) -> Error {
+    Error::Format(format!("invalid feed: {e}"))
+}
+
+impl Feed {
+    /// Parses an RSS 2.0 or Atom document.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::ddnet::Feed;
+    ///
+    /// let rss = r#"<?xml version="1.0"?>
+    /// <rss version="2.0"><channel>
+    ///   <title>DDNet Records</title>
+    ///   <item>
+    ///     <title>[Novice] Tutorial: nameless tee 00:30.00 (previous record: brainless tee 00:31.12)</title>
+    ///     <link>https://ddnet.org/maps/Tutorial</link>
+    ///     <guid>record-2</guid>
+    ///     <pubDate>Mon, 06 May 2024 20:00:00 +0200</pubDate>
+    ///   </item>
+    /// </channel></rss>"#;
+    /// let feed = Feed::parse(rss).unwrap();
+    /// assert_eq!(feed.title, "DDNet Records");
+    /// assert_eq!(feed.entries[0].id, "record-2");
+    /// assert_eq!(feed.entries[0].published.unwrap().to_string(), "2024-05-06 18:00:00");
+    ///
+    /// let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+    ///   <title>DDNet Map Releases</title>
+    ///   <entry>
+    ///     <title>[Moderate] Kobra &amp; Co by Mapper1 &amp; Mapper2</title>
+    ///     <link rel="alternate" href="https://ddnet.org/maps/Kobra-32-Co"/>
+    ///     <id>tag:ddnet.org,2024:kobra</id>
+    ///     <updated>2024-05-06T20:00:00Z</updated>
+    ///   </entry>
+    /// </feed>"#;
+    /// let feed = Feed::parse(atom).unwrap();
+    /// assert_eq!(feed.entries[0].title, "[Moderate] Kobra & Co by Mapper1 & Mapper2");
+    /// assert_eq!(feed.entries[0].link, "https://ddnet.org/maps/Kobra-32-Co");
+    /// ```
+    pub fn parse(xml: &str) -> Result<Feed> {
+        let mut reader = Reader::from_str(xml);
+        let mut feed = Feed::default();
+        let mut entry: Option<EntryBuilder> = None;
+        // The field being read, its text and the depth it started at.
+        let mut field: Option<(Field, String, usize)> = None;
+        let mut depth = 0usize;
+        let mut entry_depth = 0usize;
+
+        loop {
+            match reader.read_event().map_err(xml_error)? {
+                Event::Start(start) => {
+                    depth += 1;
+                    let name = start.local_name();
+                    match name.as_ref() {
+                        b"item" | b"entry" => {
+                            entry = Some(EntryBuilder::default());
+                            entry_depth = depth;
+                        }
+                        name => {
+                            let in_entry = entry.is_some() && depth == entry_depth + 1;
+                            let feed_title = entry.is_none() && name == b"title";
+                            if in_entry || (feed_title && feed.title.is_empty()) {
+                                field = Field::from_name(name).map(|f| (f, String::new(), depth));
+                            }
+                            if let (Some(entry), Some((Field::Link, _, _))) = (&mut entry, &field) {
+                                if let Some(href) = atom_link(&start) {
+                                    entry.set(Field::Link, href);
+                                }
+                            }
+                        }
+                    }
+                }
+                Event::Empty(start) => {
+                    if let Some(entry) = &mut entry {
+                        if start.local_name().as_ref() == b"link" && depth == entry_depth {
+                            if let Some(href) = atom_link(&start) {
+                                entry.set(Field::Link, href);
+                            }
+                        }
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some((_, value, _)) = &mut field {
+                        value.push_str(&text.decode().map_err(xml_error)?);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some((_, value, _)) = &mut field {
+                        value.push_str(&data.decode().map_err(xml_error)?);
+                    }
+                }
+                Event::GeneralRef(reference) => {
+                    if let Some((_, value, _)) = &mut field {
+                        if let Some(c) = reference.resolve_char_ref().map_err(xml_error)? {
+                            value.push(c);
+                        } else {
+                            let name = reference.decode().map_err(xml_error)?;
+                            let resolved = resolve_predefined_entity(&name)
+                                .ok_or_else(|| xml_error(format!("unknown entity &{name};")))?;
+                            value.push_str(resolved);
+                        }
+                    }
+                }
+                Event::End(end) => {
+                    if let Some((kind, value, _)) = field.take_if(|(_, _, at)| *at == depth) {
+                        let value = value.trim().to_string();
+                        match &mut entry {
+                            Some(entry) => entry.set(kind, value),
+                            None => feed.title = value,
+                        }
+                    }
+                    let name = end.local_name();
+                    if matches!(name.as_ref(), b"item" | b"entry") && depth == entry_depth {
+                        if let Some(entry) = entry.take() {
+                            feed.entries.push(entry.build());
+                        }
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(feed)
+    }
+}
+
+/// Splits a leading `[Category]` off `title`.
+fn split_category(title: &str) -> (Option<Category>, &str) {
+    let title = title.trim();
+    let parsed = title
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(category, rest)| (category.trim().parse::<Category>().ok(), rest.trim()));
+    parsed.unwrap_or((None, title))
+}
+
+/// Splits `text` into the names before a trailing race time and the time.
+fn split_time(text: &str) -> Option<(&str, RaceTime)> {
+    let text = text.trim();
+    let (names, time) = text.rsplit_once(char::is_whitespace).unwrap_or(("", text));
+    let time = RaceTime::parse(time.trim_end_matches(['.', ','])).ok()?;
+    Some((names.trim(), time))
+}
+
+/// Splits a name list joined the way ddnet.org joins them: `a, b & c`.
+///
+/// Only the separators with their spaces count, so `A&B` or `x,y` stay one
+/// name. Titles carry no quoting, though: a name that itself contains `, `
+/// or ` & ` cannot be told apart from two names and is split.
+fn split_names(names: &str) -> Vec<String> {
+    names
+        .split(", ")
+        .flat_map(|names| names.split(" & "))
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A new map record from the DDNet records feed.
+///
+/// Parsed from entry titles of the form
+/// `[Category] Map: Player & Player 00:30.00 (previous record: Holder 00:31.12)`;
+/// the category and the previous record are optional. Names are split on
+/// `, ` and ` & ` only; a name containing one of those is split as well,
+/// since the title does not quote names.
+///
+/// This grammar is modelled on hand-written titles and has not been checked
+/// against the live feed; titles it does not match give `None`.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::{FeedEntry, RecordEntry};
+/// use ddapi_rs::prelude::Category;
+///
+/// let entry = FeedEntry {
+///     title: "[Novice] Tutorial: nameless tee & brainless tee 00:30.00 (previous record: someone 00:31.12)".to_string(),
+///     ..Default::default()
+/// };
+/// let record = RecordEntry::from_entry(&entry).unwrap();
+/// assert_eq!(record.category, Some(Category::Novice));
+/// assert_eq!(record.map, "Tutorial");
+/// assert_eq!(record.players, ["nameless tee", "brainless tee"]);
+/// assert_eq!(record.previous_holders, ["someone"]);
+/// assert_eq!(record.improvement().unwrap().as_millis(), 1120);
+///
+/// // Separators count only with their spaces; one inside a name is ambiguous.
+/// let entry = FeedEntry {
+///     title: "Tutorial: A&B, x,y & Tom & Jerry 00:30.00".to_string(),
+///     ..Default::default()
+/// };
+/// let record = RecordEntry::from_entry(&entry).unwrap();
+/// assert_eq!(record.players, ["A&B", "x,y", "Tom", "Jerry"]);
+///
+/// // Parentheses inside names do not start the previous record.
+/// let entry = FeedEntry {
+///     title: "Tutorial: (1)nameless tee 00:30.00 (previous record: (2)brainless tee 00:31.12)".to_string(),
+///     ..Default::default()
+/// };
+/// let record = RecordEntry::from_entry(&entry).unwrap();
+/// assert_eq!(record.players, ["(1)nameless tee"]);
+/// assert_eq!(record.previous_holders, ["(2)brainless tee"]);
+/// assert_eq!(record.previous_time.unwrap().as_millis(), 31120);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RecordEntry {
+    pub category: Option<Category>,
+    pub map: String,
+    pub players: Vec<String>,
+    pub time: RaceTime,
+    pub previous_holders: Vec<String>,
+    pub previous_time: Option<RaceTime>,
+    pub entry: FeedEntry,
+}
+
+impl RecordEntry {
+    pub fn api() -> String {
+        format!("https://{}/records/feed/", DDNET_BASE_URL)
+    }
+
+    /// Parses the entry title, `None` if it does not look like a record.
+    pub fn from_entry(entry: &FeedEntry) -> Option<RecordEntry> {
+        let (category, rest) = split_category(&entry.title);
+        let (map, rest) = rest.split_once(": ")?;
+        // Anchor on the label rather than any `(`, which names may contain.
+        let (current, previous) = match rest.split_once(" (previous record") {
+            Some((current, previous)) => {
+                let previous = previous.trim_end().trim_end_matches(')');
+                (current, Some(previous.trim_start_matches(':')))
+            }
+            None => (rest, None),
+        };
+        let (players, time) = split_time(current)?;
+        let previous = previous.and_then(split_time);
+
+        Some(RecordEntry {
+            category,
+            map: map.trim().to_string(),
+            players: split_names(players),
+            time,
+            previous_holders: previous
+                .map(|(names, _)| split_names(names))
+                .unwrap_or_default(),
+            previous_time: previous.map(|(_, time)| time),
+            entry: entry.clone(),
+        })
+    }
+
+    /// Time gained on the previous record.
+    pub fn improvement(&self) -> Option<RaceTime> {
+        Some(self.previous_time? - self.time)
+    }
+}
+
+/// A map release from the DDNet releases feed.
+///
+/// Parsed from entry titles of the form `[Category] Map by Mapper & Mapper`,
+/// with mapper names split like the players of a [`RecordEntry`]. Like that
+/// one, the grammar is unverified against the live feed.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::{FeedEntry, ReleaseEntry};
+///
+/// let entry = FeedEntry {
+///     title: "[Moderate] Kobra 3 by Mapper1, Mapper2 & Mapper3".to_string(),
+///     ..Default::default()
+/// };
+/// let release = ReleaseEntry::from_entry(&entry).unwrap();
+/// assert_eq!(release.map, "Kobra 3");
+/// assert_eq!(release.mappers.len(), 3);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ReleaseEntry {
+    pub category: Option<Category>,
+    pub map: String,
+    pub mappers: Vec<String>,
+    pub entry: FeedEntry,
+}
+
+impl ReleaseEntry {
+    pub fn api() -> String {
+        format!("https://{}/releases/feed/", DDNET_BASE_URL)
+    }
+
+    pub fn from_entry(entry: &FeedEntry) -> Option<ReleaseEntry> {
+        let (category, rest) = split_category(&entry.title);
+        let (map, mappers) = match rest.rsplit_once(" by ") {
+            Some((map, mappers)) => (map, split_names(mappers)),
+            None => (rest, Vec::new()),
+        };
+        if map.trim().is_empty() {
+            return None;
+        }
+        Some(ReleaseEntry {
+            category,
+            map: map.trim().to_string(),
+            mappers,
+            entry: entry.clone(),
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::prelude::RaceTime;
 use crate::scheme::DDNET_BASE_URL;
 use crate::scheme::{deserialize_datetime_timestamp, serialize_datetime_timestamp};
 use chrono::NaiveDateTime;
@@ -17,6 +18,10 @@ pub struct LatestFinishes {
 }
 
 impl LatestFinishes {
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+
     pub fn url() -> String {
         format!("https://{}/ranks", DDNET_BASE_URL)
     }
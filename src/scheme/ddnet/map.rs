@@ -1,4 +1,4 @@
-use crate::prelude::{encode, slugify2};
+use crate::prelude::{encode, slugify2, RaceTime};
 use crate::scheme::{deserialize_datetime_timestamp, serialize_datetime_timestamp, DDNET_BASE_URL};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
@@ -44,6 +44,11 @@ impl Map {
     pub fn api(map: &str) -> String {
         format!("https://{}/maps/?json={}", DDNET_BASE_URL, encode(map))
     }
+
+    /// Download URL of the current version of a released map on the DDNet map mirror.
+    pub fn download_url_with_name(map: &str) -> String {
+        format!("https://maps.{}/{}.map", DDNET_BASE_URL, encode(map))
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -59,6 +64,12 @@ pub struct DTeamRank {
     pub country: String,
 }
 
+impl DTeamRank {
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DRank {
     pub rank: i64,
@@ -72,6 +83,12 @@ pub struct DRank {
     pub country: String,
 }
 
+impl DRank {
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MaxFinish {
     pub rank: i64,
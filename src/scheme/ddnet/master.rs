@@ -1,5 +1,5 @@
-use crate::prelude::Addr;
 use crate::prelude::{addr_serialization, Protocol};
+use crate::prelude::{encode, is_sha256, Addr};
 use crate::scheme::DDNET_BASE_URL;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -159,6 +159,24 @@ pub struct IMap {
     pub size: Option<i64>,
 }
 
+impl IMap {
+    /// The reported sha256, if it is a well-formed hex digest.
+    pub fn valid_sha256(&self) -> Option<&str> {
+        self.sha256.as_deref().filter(|sha256| is_sha256(sha256))
+    }
+
+    /// Download URL on the DDNet map mirror, when the server reported a valid sha256.
+    pub fn download_url(&self) -> Option<String> {
+        let sha256 = self.valid_sha256()?;
+        Some(format!(
+            "https://maps.{}/{}_{}.map",
+            DDNET_BASE_URL,
+            encode(&self.name),
+            sha256
+        ))
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Client {
     pub name: String,
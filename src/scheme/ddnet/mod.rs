@@ -1,22 +1,34 @@
+mod compare;
+mod completion;
+#[cfg(feature = "feeds")]
+mod feed;
 mod latest_finishes;
 mod map;
 mod master;
 mod player;
+mod points_calc;
 mod query;
 mod query_map;
 mod query_mapper;
+mod records;
 mod releases_maps;
 mod skins;
 mod status;
 
 pub mod prelude {
+    pub use crate::scheme::ddnet::compare::*;
+    pub use crate::scheme::ddnet::completion::*;
+    #[cfg(feature = "feeds")]
+    pub use crate::scheme::ddnet::feed::*;
     pub use crate::scheme::ddnet::latest_finishes::*;
     pub use crate::scheme::ddnet::map::*;
     pub use crate::scheme::ddnet::master::*;
     pub use crate::scheme::ddnet::player::*;
+    pub use crate::scheme::ddnet::points_calc::*;
     pub use crate::scheme::ddnet::query::*;
     pub use crate::scheme::ddnet::query_map::*;
     pub use crate::scheme::ddnet::query_mapper::*;
+    pub use crate::scheme::ddnet::records::*;
     pub use crate::scheme::ddnet::releases_maps::*;
     pub use crate::scheme::ddnet::skins::*;
     pub use crate::scheme::ddnet::status::*;
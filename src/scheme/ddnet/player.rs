@@ -1,5 +1,7 @@
-use crate::prelude::{encode, slugify2};
-use crate::scheme::{deserialize_datetime_timestamp, serialize_datetime_timestamp, DDNET_BASE_URL};
+use crate::prelude::{encode, slugify2, RaceTime};
+use crate::scheme::{
+    deserialize_datetime_timestamp, serialize_datetime_timestamp, Category, DDNET_BASE_URL,
+};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -120,6 +122,92 @@ pub struct Types {
     pub fun: Type,
 }
 
+impl Types {
+    /// Returns the breakdown for a single category.
+    pub fn get(&self, category: Category) -> &Type {
+        match category {
+            Category::Novice => &self.novice,
+            Category::Moderate => &self.moderate,
+            Category::Brutal => &self.brutal,
+            Category::Insane => &self.insane,
+            Category::Dummy => &self.dummy,
+            Category::DDmaXEasy => &self.ddmax_easy,
+            Category::DDmaXNext => &self.ddmax_next,
+            Category::DDmaXPro => &self.ddmax_pro,
+            Category::DDmaXNut => &self.ddmax_nut,
+            Category::Oldschool => &self.oldschool,
+            Category::Solo => &self.solo,
+            Category::Race => &self.race,
+            Category::Fun => &self.fun,
+        }
+    }
+
+    pub fn get_mut(&mut self, category: Category) -> &mut Type {
+        match category {
+            Category::Novice => &mut self.novice,
+            Category::Moderate => &mut self.moderate,
+            Category::Brutal => &mut self.brutal,
+            Category::Insane => &mut self.insane,
+            Category::Dummy => &mut self.dummy,
+            Category::DDmaXEasy => &mut self.ddmax_easy,
+            Category::DDmaXNext => &mut self.ddmax_next,
+            Category::DDmaXPro => &mut self.ddmax_pro,
+            Category::DDmaXNut => &mut self.ddmax_nut,
+            Category::Oldschool => &mut self.oldschool,
+            Category::Solo => &mut self.solo,
+            Category::Race => &mut self.race,
+            Category::Fun => &mut self.fun,
+        }
+    }
+
+    /// Iterates over every category in [`Category::ALL`] order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::ddnet::Types;
+    ///
+    /// let mut types = Types::default();
+    /// types.insane.points.points = Some(120);
+    /// types.fun.points.points = Some(30);
+    ///
+    /// let earned: u64 = types.iter().filter_map(|(_, t)| t.points.points).sum();
+    /// assert_eq!(earned, 150);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Category, &Type)> {
+        Category::ALL.into_iter().map(move |c| (c, self.get(c)))
+    }
+
+    /// Flattened view of every map across all categories.
+    pub fn maps(&self) -> impl Iterator<Item = (Category, &str, &DDMap)> {
+        self.iter().flat_map(|(category, t)| {
+            t.maps
+                .iter()
+                .map(move |(name, map)| (category, name.as_str(), map))
+        })
+    }
+
+    /// Every map with at least one finish, across all categories.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::Category;
+    /// use ddapi_rs::prelude::ddnet::{DDMap, Types};
+    ///
+    /// let mut types = Types::default();
+    /// types.novice.maps.insert(
+    ///     "Tutorial".to_string(),
+    ///     DDMap { finishes: 3, ..Default::default() },
+    /// );
+    /// types.brutal.maps.insert("Bloody".to_string(), DDMap::default());
+    ///
+    /// let finished: Vec<_> = types.finished_maps().map(|(c, name, _)| (c, name)).collect();
+    /// assert_eq!(finished, vec![(Category::Novice, "Tutorial")]);
+    /// ```
+    pub fn finished_maps(&self) -> impl Iterator<Item = (Category, &str, &DDMap)> {
+        self.maps().filter(|(_, _, map)| map.finishes > 0)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Type {
     pub points: Points,
@@ -139,6 +227,13 @@ pub struct DDMap {
     pub first_finish: Option<f64>,
 }
 
+impl DDMap {
+    /// Best time on this map, if the player has finished it.
+    pub fn race_time(&self) -> Option<RaceTime> {
+        self.time.map(RaceTime::from_secs_f64)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Activity {
     pub date: String,
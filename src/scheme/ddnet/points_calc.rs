@@ -0,0 +1,263 @@
+use crate::scheme::ddnet::map::Map;
+use crate::scheme::ddnet::player::Player;
+use crate::scheme::ddnet::releases_maps::ReleasesMaps;
+use crate::scheme::Category;
+
+/// Rank points awarded for places 1 to 10, for both solo and team ranks.
+pub const RANK_POINTS: [u64; 10] = [25, 18, 15, 12, 10, 8, 6, 4, 2, 1];
+
+/// `(base, per star)` map points of a category, as published on ddnet.org/ranks.
+///
+/// Not checked against a recorded `releases/maps.json`; [`points_mismatches`]
+/// reports where the API disagrees with it.
+fn category_factors(category: Category) -> (u64, u64) {
+    match category {
+        Category::Novice => (0, 1),
+        Category::Moderate => (5, 2),
+        Category::Brutal => (15, 3),
+        Category::Insane => (30, 5),
+        Category::Dummy => (5, 5),
+        Category::DDmaXEasy | Category::DDmaXNext | Category::DDmaXPro | Category::DDmaXNut => {
+            (0, 4)
+        }
+        Category::Oldschool => (0, 6),
+        Category::Solo => (0, 4),
+        Category::Race => (0, 2),
+        Category::Fun => (0, 1),
+    }
+}
+
+/// Points a map of `category` with `stars` difficulty awards on first finish.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::Category;
+/// use ddapi_rs::prelude::ddnet::map_points;
+///
+/// assert_eq!(map_points(Category::Novice, 3), 3);
+/// assert_eq!(map_points(Category::Moderate, 5), 15);
+/// assert_eq!(map_points(Category::Brutal, 2), 21);
+/// assert_eq!(map_points(Category::Insane, 5), 55);
+/// ```
+pub fn map_points(category: Category, stars: u8) -> u64 {
+    let (base, per_star) = category_factors(category);
+    base + per_star * stars as u64
+}
+
+/// Rank points for a solo or team `rank`; zero outside the top 10.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::rank_points;
+///
+/// assert_eq!(rank_points(1), 25);
+/// assert_eq!(rank_points(10), 1);
+/// assert_eq!(rank_points(11), 0);
+/// ```
+pub fn rank_points(rank: u64) -> u64 {
+    match rank {
+        1..=10 => RANK_POINTS[rank as usize - 1],
+        _ => 0,
+    }
+}
+
+impl Map {
+    /// Best solo rank of `player` in [`Map::ranks`].
+    pub fn player_rank(&self, player: &str) -> Option<u64> {
+        self.ranks
+            .iter()
+            .filter(|r| r.player == player)
+            .map(|r| r.rank as u64)
+            .min()
+    }
+
+    /// Best team rank of `player` in [`Map::team_ranks`].
+    pub fn player_team_rank(&self, player: &str) -> Option<u64> {
+        self.team_ranks
+            .iter()
+            .filter(|r| r.players.iter().any(|p| p == player))
+            .map(|r| r.rank as u64)
+            .min()
+    }
+
+    /// The solo rank a finish in `time` seconds would get, assuming it is
+    /// compared against [`Map::ranks`]. Equal times share a rank, like on ddnet.org.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::ddnet::*;
+    ///
+    /// let rank = |rank: i64, player: &str, time: f64| DRank {
+    ///     rank,
+    ///     player: player.to_string(),
+    ///     time,
+    ///     ..Default::default()
+    /// };
+    /// let map = Map {
+    ///     ranks: vec![rank(1, "a", 30.0), rank(2, "b", 31.5), rank(2, "c", 31.5)],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(map.rank_for_time(29.0), 1);
+    /// assert_eq!(map.rank_for_time(31.5), 2);
+    /// assert_eq!(map.rank_for_time(40.0), 4);
+    /// assert_eq!(rank_points(map.rank_for_time(31.0)), 18);
+    /// ```
+    pub fn rank_for_time(&self, time: f64) -> u64 {
+        self.ranks.iter().filter(|r| r.time < time).count() as u64 + 1
+    }
+
+    /// Team rank counterpart of [`Map::rank_for_time`].
+    pub fn team_rank_for_time(&self, time: f64) -> u64 {
+        self.team_ranks.iter().filter(|r| r.time < time).count() as u64 + 1
+    }
+}
+
+/// Points of a player recomputed from raw data instead of taken from the API totals.
+///
+/// `points` sums the per-map [`DDMap::points`](crate::scheme::ddnet::prelude::DDMap::points)
+/// of the finished maps in [`Player::types`] rather than [`map_points`], since
+/// the player data carries no star ratings; [`points_mismatches`] checks those
+/// per-map values against [`map_points`] using the releases. `rank_points`
+/// and `team_points` come from the `ranks`/`team_ranks` of the supplied maps,
+/// so they are only complete when every map the player holds a top 10 on is
+/// included.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::*;
+///
+/// let mut player = Player { player: "a".to_string(), ..Default::default() };
+/// player.types.novice.maps.insert(
+///     "Tutorial".to_string(),
+///     DDMap { points: 1, finishes: 2, ..Default::default() },
+/// );
+///
+/// let map = Map {
+///     name: "Tutorial".to_string(),
+///     ranks: vec![DRank { rank: 3, player: "a".to_string(), ..Default::default() }],
+///     team_ranks: vec![DTeamRank {
+///         rank: 1,
+///         players: vec!["a".to_string(), "b".to_string()],
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// let breakdown = PointsBreakdown::new(&player, &[map]);
+/// assert_eq!(breakdown, PointsBreakdown { points: 1, rank_points: 15, team_points: 25 });
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointsBreakdown {
+    pub points: u64,
+    pub rank_points: u64,
+    pub team_points: u64,
+}
+
+impl PointsBreakdown {
+    pub fn new(player: &Player, maps: &[Map]) -> Self {
+        let points = player
+            .types
+            .finished_maps()
+            .map(|(_, _, map)| map.points.max(0) as u64)
+            .sum();
+
+        let solo = maps
+            .iter()
+            .filter_map(|m| m.player_rank(&player.player))
+            .map(rank_points)
+            .sum();
+
+        let team = maps
+            .iter()
+            .filter_map(|m| m.player_team_rank(&player.player))
+            .map(rank_points)
+            .sum();
+
+        Self {
+            points,
+            rank_points: solo,
+            team_points: team,
+        }
+    }
+
+    /// The totals ddnet.org reports for `player`, for comparison with [`PointsBreakdown::new`].
+    pub fn reported(player: &Player) -> Self {
+        Self {
+            points: player.points.points.unwrap_or(0),
+            rank_points: player.rank.as_ref().and_then(|r| r.points).unwrap_or(0),
+            team_points: player
+                .team_rank
+                .as_ref()
+                .and_then(|r| r.points)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// A map whose points disagree with [`map_points`] for its category and stars.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointsMismatch {
+    pub map: String,
+    pub category: Category,
+    pub stars: u8,
+    pub expected: u64,
+    /// Points in `releases/maps.json`.
+    pub released: u64,
+    /// Points in the player's data, if they finished the map.
+    pub player: Option<u64>,
+}
+
+/// Validates the points in `releases` and in `player`'s finished maps against [`map_points`].
+///
+/// Releases of an unknown category are skipped.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::Category;
+/// use ddapi_rs::prelude::ddnet::*;
+///
+/// let release = |name: &str, r#type: &str, points: u8, difficulty: u8| ReleasesMaps {
+///     name: name.to_string(),
+///     r#type: r#type.to_string(),
+///     points,
+///     difficulty,
+///     ..Default::default()
+/// };
+/// let releases = vec![release("Kobra 4", "Moderate", 11, 3), release("Lost", "Insane", 50, 4)];
+///
+/// let mut player = Player::default();
+/// player.types.moderate.maps.insert(
+///     "Kobra 4".to_string(),
+///     DDMap { points: 12, finishes: 1, ..Default::default() },
+/// );
+///
+/// let mismatches = points_mismatches(&player, &releases);
+/// assert_eq!(mismatches.len(), 1);
+/// assert_eq!((mismatches[0].expected, mismatches[0].player), (11, Some(12)));
+/// ```
+pub fn points_mismatches(player: &Player, releases: &[ReleasesMaps]) -> Vec<PointsMismatch> {
+    releases
+        .iter()
+        .filter_map(|release| {
+            let category = release.category()?;
+            let expected = map_points(category, release.difficulty);
+            let player = player
+                .types
+                .finished_maps()
+                .find(|(_, name, _)| *name == release.name)
+                .map(|(_, _, map)| map.points.max(0) as u64);
+            let released = release.points as u64;
+            (released != expected || player.is_some_and(|p| p != expected)).then(|| {
+                PointsMismatch {
+                    map: release.name.clone(),
+                    category,
+                    stars: release.difficulty,
+                    expected,
+                    released,
+                    player,
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,253 @@
+use crate::prelude::RaceTime;
+use crate::scheme::ddnet::latest_finishes::LatestFinishes;
+use crate::scheme::ddnet::map::{DRank, DTeamRank, Map};
+
+/// Number of ranks counted as a top-10 finish.
+pub const TOP_RANKS: i64 = 10;
+
+/// What a finish means for the player and the map.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct FinishClass {
+    /// The player had never finished the map before.
+    pub first_finish: bool,
+    /// Faster than every earlier finish of the player (always true for a first finish).
+    pub personal_best: bool,
+    pub previous_best: Option<f64>,
+    /// Rank the finish takes, `None` when it is slower than every listed rank.
+    pub rank: Option<i64>,
+    /// The player's listed rank before this finish.
+    pub previous_rank: Option<i64>,
+    /// Team rank the time would take, if it is within the listed team ranks.
+    pub team_rank: Option<i64>,
+    pub top10: bool,
+    /// Strictly faster than the previous record (or the map had no finish yet).
+    pub world_record: bool,
+    pub previous_record: Option<f64>,
+    pub time: f64,
+}
+
+impl FinishClass {
+    /// Seconds gained on the previous personal best.
+    pub fn improvement(&self) -> Option<RaceTime> {
+        let previous = self.previous_best?;
+        self.personal_best
+            .then(|| RaceTime::from_secs_f64(previous) - RaceTime::from_secs_f64(self.time))
+    }
+
+    /// Seconds gained on the previous record.
+    pub fn record_improvement(&self) -> Option<RaceTime> {
+        let previous = self.previous_record?;
+        self.world_record
+            .then(|| RaceTime::from_secs_f64(previous) - RaceTime::from_secs_f64(self.time))
+    }
+}
+
+/// What is known about a player's finishes on a map before the one being
+/// classified, besides the listed ranks.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum PreviousBest {
+    /// Nothing is known; the listed ranks decide, and without a listed rank
+    /// the finish is the player's first.
+    #[default]
+    NotFinished,
+    /// The player's best time before this finish.
+    Time(f64),
+    /// The player finished before, but every earlier time is unknown and
+    /// slower, e.g. when the fetched data already counts this finish as their best.
+    Slower,
+}
+
+impl From<Option<f64>> for PreviousBest {
+    fn from(time: Option<f64>) -> Self {
+        time.map_or(PreviousBest::NotFinished, PreviousBest::Time)
+    }
+}
+
+/// The ranks of one map, kept up to date with incoming finishes.
+///
+/// Built from [`Map`]; [`MapRecords::classify`] tells what a finish means
+/// and [`MapRecords::apply`] adds it to the ranks so the next finish is
+/// classified against it without fetching the map again.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddnet::{DRank, LatestFinishes, Map, MapRecords, PreviousBest};
+///
+/// let rank = |rank: i64, player: &str, time: f64| DRank {
+///     rank,
+///     player: player.to_string(),
+///     time,
+///     ..Default::default()
+/// };
+/// let map = Map {
+///     name: "Multeasymap".to_string(),
+///     finishers: 3,
+///     ranks: vec![rank(1, "a", 100.0), rank(2, "b", 110.0), rank(3, "c", 120.0)],
+///     ..Default::default()
+/// };
+/// let mut records = MapRecords::new(&map);
+///
+/// let finish = LatestFinishes {
+///     map: "Multeasymap".to_string(),
+///     name: "c".to_string(),
+///     time: 105.0,
+///     ..Default::default()
+/// };
+/// let class = records.classify(&finish, None);
+/// assert!(class.personal_best && class.top10 && !class.world_record);
+/// assert_eq!((class.previous_rank, class.rank), (Some(3), Some(2)));
+/// assert_eq!(class.improvement().unwrap().as_millis(), 15_000);
+///
+/// records.apply(&finish);
+/// let record = LatestFinishes { name: "d".to_string(), time: 99.5, ..finish };
+/// let class = records.classify(&record, None);
+/// assert!(class.first_finish && class.world_record);
+/// assert_eq!(class.record_improvement().unwrap().as_millis(), 500);
+///
+/// // Ranks fetched after the finish already list it; only the player's data
+/// // tells whether it was their first.
+/// records.apply(&record);
+/// assert!(records.includes(&record));
+/// let class = records.classify(&record, PreviousBest::Slower);
+/// assert!(!class.first_finish && class.personal_best && class.previous_best.is_none());
+/// ```
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct MapRecords {
+    pub map: String,
+    pub ranks: Vec<DRank>,
+    pub team_ranks: Vec<DTeamRank>,
+    pub finishers: i64,
+}
+
+impl MapRecords {
+    pub fn new(map: &Map) -> Self {
+        let mut records = Self {
+            map: map.name.clone(),
+            ranks: map.ranks.clone(),
+            team_ranks: map.team_ranks.clone(),
+            finishers: map.finishers,
+        };
+        records.ranks.sort_by(|a, b| a.time.total_cmp(&b.time));
+        records.team_ranks.sort_by(|a, b| a.time.total_cmp(&b.time));
+        records
+    }
+
+    pub fn record(&self) -> Option<&DRank> {
+        self.ranks.first()
+    }
+
+    /// The player's listed rank, if they are among the listed ranks.
+    pub fn rank_of(&self, player: &str) -> Option<&DRank> {
+        self.ranks.iter().find(|rank| rank.player == player)
+    }
+
+    /// Whether the ranks already list this very finish, i.e. they were fetched after it.
+    pub fn includes(&self, finish: &LatestFinishes) -> bool {
+        self.rank_of(&finish.name)
+            .is_some_and(|rank| rank.time == finish.time && rank.timestamp == finish.timestamp)
+    }
+
+    /// Whether every finisher of the map is listed, so any time can be ranked.
+    pub fn complete(&self) -> bool {
+        self.finishers <= self.ranks.len() as i64
+    }
+
+    /// Classifies `finish`.
+    ///
+    /// `previous_best` is what is known about the player from elsewhere (e.g.
+    /// [`DDMap::time`](crate::scheme::ddnet::prelude::DDMap::time)), needed
+    /// when they are not among the listed ranks; `None` converts to
+    /// [`PreviousBest::NotFinished`]. If the ranks already contain this very
+    /// finish, it is ignored.
+    pub fn classify(
+        &self,
+        finish: &LatestFinishes,
+        previous_best: impl Into<PreviousBest>,
+    ) -> FinishClass {
+        let is_this = |rank: &DRank| {
+            rank.player == finish.name
+                && rank.time == finish.time
+                && rank.timestamp == finish.timestamp
+        };
+        let (previous_best, finished_before) = match previous_best.into() {
+            PreviousBest::NotFinished => (None, false),
+            PreviousBest::Time(time) => (Some(time), true),
+            PreviousBest::Slower => (None, true),
+        };
+        let others: Vec<&DRank> = self
+            .ranks
+            .iter()
+            .filter(|rank| rank.player != finish.name)
+            .collect();
+        let own = self.rank_of(&finish.name).filter(|rank| !is_this(rank));
+
+        let previous_best = match (own.map(|rank| rank.time), previous_best) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let previous_record = self
+            .ranks
+            .iter()
+            .filter(|rank| !is_this(rank))
+            .map(|rank| rank.time)
+            .reduce(f64::min);
+
+        let personal_best = previous_best.is_none_or(|best| finish.time < best);
+        let faster = others.iter().filter(|rank| rank.time < finish.time).count();
+        let listed = faster < others.len() || self.complete();
+        let rank = (personal_best && listed).then_some(faster as i64 + 1);
+
+        let team_faster = self
+            .team_ranks
+            .iter()
+            .filter(|rank| rank.time < finish.time)
+            .count();
+        let team_rank = (team_faster < self.team_ranks.len()).then_some(team_faster as i64 + 1);
+
+        FinishClass {
+            first_finish: !finished_before && previous_best.is_none(),
+            personal_best,
+            previous_best,
+            rank,
+            previous_rank: own.map(|rank| rank.rank),
+            team_rank,
+            top10: rank.is_some_and(|rank| rank <= TOP_RANKS),
+            world_record: previous_record.is_none_or(|record| finish.time < record),
+            previous_record,
+            time: finish.time,
+        }
+    }
+
+    /// Adds `finish` to the ranks if it is the player's best, re-numbering the ranks.
+    pub fn apply(&mut self, finish: &LatestFinishes) {
+        let previous = self.rank_of(&finish.name).map(|rank| rank.time);
+        if previous.is_some_and(|time| finish.time >= time) {
+            return;
+        }
+        if previous.is_none() && self.complete() {
+            self.finishers += 1;
+        }
+        self.ranks.retain(|rank| rank.player != finish.name);
+        let index = self.ranks.partition_point(|rank| rank.time <= finish.time);
+        self.ranks.insert(
+            index,
+            DRank {
+                rank: 0,
+                player: finish.name.clone(),
+                time: finish.time,
+                timestamp: finish.timestamp,
+                country: String::new(),
+            },
+        );
+
+        // Equal times share a rank, like on ddnet.org.
+        let mut last: Option<(f64, i64)> = None;
+        for (i, rank) in self.ranks.iter_mut().enumerate() {
+            rank.rank = match last {
+                Some((time, shared)) if time == rank.time => shared,
+                _ => i as i64 + 1,
+            };
+            last = Some((rank.time, rank.rank));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::scheme::DDNET_BASE_URL;
+use crate::scheme::{Category, DDNET_BASE_URL};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -26,4 +26,9 @@ impl ReleasesMaps {
     pub fn api() -> String {
         format!("https://{}/releases/maps.json", DDNET_BASE_URL)
     }
+
+    /// The map's category, `None` if `type` is not a known [`Category`].
+    pub fn category(&self) -> Option<Category> {
+        self.r#type.parse().ok()
+    }
 }
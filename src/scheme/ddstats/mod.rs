@@ -1,7 +1,23 @@
-use crate::scheme::DDSTATS_BASE_URL;
-use crate::util::prelude::{encode, seconds_to_hours, slugify2};
+use crate::scheme::{Category, DDSTATS_BASE_URL};
+use crate::util::prelude::{
+    encode, seconds_to_hours, slugify2, LenientDate, LenientDateTime, RaceTime,
+};
 use serde::{Deserialize, Serialize};
 
+mod leaderboard;
+mod playtime;
+mod points_history;
+mod search;
+mod servers;
+mod splits;
+
+pub use leaderboard::*;
+pub use playtime::*;
+pub use points_history::*;
+pub use search::*;
+pub use servers::*;
+pub use splits::*;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     pub points_graph: Vec<PointsGraph>,
@@ -58,7 +74,7 @@ impl Player {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PointsGraph {
-    pub date: String,
+    pub date: LenientDate,
     pub points: i64,
     pub rank_points: i64,
     pub team_points: i64,
@@ -69,33 +85,10 @@ pub struct RecentFinish {
     pub map: StatsMap,
     pub name: String,
     pub time: f64,
-    pub timestamp: String,
+    pub timestamp: LenientDateTime,
     pub server: String,
-    pub cp1: f64,
-    pub cp2: f64,
-    pub cp3: f64,
-    pub cp4: f64,
-    pub cp5: f64,
-    pub cp6: f64,
-    pub cp7: f64,
-    pub cp8: f64,
-    pub cp9: f64,
-    pub cp10: f64,
-    pub cp11: f64,
-    pub cp12: f64,
-    pub cp13: f64,
-    pub cp14: f64,
-    pub cp15: f64,
-    pub cp16: f64,
-    pub cp17: f64,
-    pub cp18: f64,
-    pub cp19: f64,
-    pub cp20: f64,
-    pub cp21: f64,
-    pub cp22: f64,
-    pub cp23: f64,
-    pub cp24: f64,
-    pub cp25: f64,
+    #[serde(flatten)]
+    pub checkpoints: Checkpoints,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -105,7 +98,7 @@ pub struct StatsMap {
     pub points: u8,
     pub stars: u8,
     pub mapper: String,
-    pub timestamp: Option<String>,
+    pub timestamp: Option<LenientDateTime>,
 }
 
 impl StatsMap {
@@ -171,13 +164,19 @@ pub struct Finish {
     pub map: StatsMap,
     pub name: String,
     pub time: f64,
-    pub timestamp: String,
+    pub timestamp: LenientDateTime,
     pub server: String,
     pub rank: u64,
     pub team_rank: Option<u64>,
     pub seconds_played: Option<u64>,
 }
 
+impl Finish {
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UnfinishedMap {
     pub map: StatsMap,
@@ -225,6 +224,48 @@ pub struct StatsPoints {
     pub ddmax_next: Option<Type>,
 }
 
+impl StatsPoints {
+    /// Returns the entry for a single category, if ddstats reported one.
+    pub fn get(&self, category: Category) -> Option<&Type> {
+        match category {
+            Category::Novice => self.novice.as_ref(),
+            Category::Moderate => self.moderate.as_ref(),
+            Category::Brutal => self.brutal.as_ref(),
+            Category::Insane => self.insane.as_ref(),
+            Category::Dummy => self.dummy.as_ref(),
+            Category::DDmaXEasy => self.ddmax_easy.as_ref(),
+            Category::DDmaXNext => self.ddmax_next.as_ref(),
+            Category::DDmaXPro => self.ddmax_pro.as_ref(),
+            Category::DDmaXNut => self.ddmax_nut.as_ref(),
+            Category::Oldschool => self.oldschool.as_ref(),
+            Category::Solo => self.solo.as_ref(),
+            Category::Race => self.race.as_ref(),
+            Category::Fun => self.fun.as_ref(),
+        }
+    }
+
+    /// Iterates over the categories present in the response, excluding `total`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::Category;
+    /// use ddapi_rs::prelude::ddstats::{StatsPoints, Type};
+    ///
+    /// let points = StatsPoints {
+    ///     solo: Some(Type { points: 40, rank: 900 }),
+    ///     total: Some(Type { points: 40, rank: 12000 }),
+    ///     ..Default::default()
+    /// };
+    /// let categories: Vec<_> = points.iter().map(|(c, _)| c).collect();
+    /// assert_eq!(categories, vec![Category::Solo]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Category, &Type)> {
+        Category::ALL
+            .into_iter()
+            .filter_map(move |c| self.get(c).map(|t| (c, t)))
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Type {
     pub points: i64,
@@ -234,7 +275,7 @@ pub struct Type {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RecentActivity {
     pub name: String,
-    pub date: String,
+    pub date: LenientDate,
     pub map_name: String,
     pub map: Option<StatsMap>,
     pub seconds_played: i64,
@@ -248,7 +289,7 @@ pub struct RecentPlayerInfo {
     pub skin_name: Option<String>,
     pub skin_color_body: Option<u64>,
     pub skin_color_feet: Option<u64>,
-    pub last_seen: String,
+    pub last_seen: LenientDateTime,
     pub seconds_played: u64,
 }
 
@@ -267,7 +308,7 @@ pub struct MostPlayed {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlaytimePerMonth {
-    pub year_month: String,
+    pub year_month: LenientDate,
     pub month: String,
     pub seconds_played: u64,
 }
@@ -275,7 +316,7 @@ pub struct PlaytimePerMonth {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeneralActivity {
     pub total_seconds_played: u64,
-    pub start_of_playtime: String,
+    pub start_of_playtime: LenientDateTime,
     pub average_seconds_played: u64,
 }
 
@@ -311,7 +352,7 @@ pub struct RecentTop10 {
     pub map: String,
     pub time: f64,
     pub rank: u64,
-    pub timestamp: String,
+    pub timestamp: LenientDateTime,
     pub server: String,
 }
 
@@ -326,7 +367,7 @@ pub struct InfoSMap {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RankingSMap {
     pub rank: u64,
-    pub timestamp: Option<String>,
+    pub timestamp: Option<LenientDateTime>,
     pub name: String,
     pub time: f64,
     pub map: String,
@@ -336,7 +377,7 @@ pub struct RankingSMap {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TeamRankingSMap {
     pub rank: u64,
-    pub timestamp: Option<String>,
+    pub timestamp: Option<LenientDateTime>,
     pub id: Vec<u64>,
     pub players: Vec<String>,
     pub time: f64,
@@ -347,31 +388,8 @@ pub struct TeamRankingSMap {
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeCpsSMap {
     pub name: String,
-    pub cp1: f64,
-    pub cp2: f64,
-    pub cp3: f64,
-    pub cp4: f64,
-    pub cp5: f64,
-    pub cp6: f64,
-    pub cp7: f64,
-    pub cp8: f64,
-    pub cp9: f64,
-    pub cp10: f64,
-    pub cp11: f64,
-    pub cp12: f64,
-    pub cp13: f64,
-    pub cp14: f64,
-    pub cp15: f64,
-    pub cp16: f64,
-    pub cp17: f64,
-    pub cp18: f64,
-    pub cp19: f64,
-    pub cp20: f64,
-    pub cp21: f64,
-    pub cp22: f64,
-    pub cp23: f64,
-    pub cp24: f64,
-    pub cp25: f64,
+    #[serde(flatten)]
+    pub checkpoints: Checkpoints,
     pub time: f64,
 }
 
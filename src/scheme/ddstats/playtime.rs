@@ -0,0 +1,371 @@
+use super::{MostPlayed, Player};
+use crate::scheme::Category;
+use crate::util::prelude::{seconds_to_hours, RaceTime};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// Part of the day, by the hour (as reported by ddstats, UTC).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimeOfDay {
+    /// 00:00 – 05:59
+    Night,
+    /// 06:00 – 11:59
+    Morning,
+    /// 12:00 – 17:59
+    Afternoon,
+    /// 18:00 – 23:59
+    Evening,
+}
+
+impl TimeOfDay {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            0..=5 => TimeOfDay::Night,
+            6..=11 => TimeOfDay::Morning,
+            12..=17 => TimeOfDay::Afternoon,
+            _ => TimeOfDay::Evening,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeOfDay::Night => "night",
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Evening => "evening",
+        }
+    }
+}
+
+/// Seconds played under one key (category, location, ...) and its share of the total.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PlaytimeShare<K = String> {
+    pub key: K,
+    pub seconds_played: u64,
+    /// `0.0..=1.0`
+    pub share: f64,
+}
+
+impl<K> PlaytimeShare<K> {
+    pub fn seconds_played_to_hours(&self) -> f64 {
+        seconds_to_hours(self.seconds_played as f64)
+    }
+}
+
+/// A run of finishes without long breaks in between.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Session {
+    /// When the first run of the session started (its finish minus its race time).
+    pub start: NaiveDateTime,
+    /// The last finish of the session.
+    pub end: NaiveDateTime,
+    pub finishes: usize,
+}
+
+impl Session {
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    pub fn hours(&self) -> f64 {
+        seconds_to_hours(self.duration().num_seconds() as f64)
+    }
+}
+
+/// Consecutive days with recorded playtime.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActiveStreak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl ActiveStreak {
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+}
+
+/// Playtime analytics over a ddstats player profile.
+///
+/// Day-based figures come from `recent_activity`. ddstats only records
+/// playtime per day, so hour-based figures (heatmap, time of day, sessions)
+/// use the timestamps of the player's finishes instead.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::Player;
+///
+/// let mut player = Player::default();
+/// player.recent_activity = serde_json::from_str(r#"[
+///     {"name": "nameless tee", "date": "2024-05-06", "map_name": "Tutorial", "map": null, "seconds_played": 3600},
+///     {"name": "nameless tee", "date": "2024-05-07", "map_name": "Tutorial", "map": null, "seconds_played": 1800},
+///     {"name": "nameless tee", "date": "2024-05-07", "map_name": "Multeasymap", "map": null, "seconds_played": 1800},
+///     {"name": "nameless tee", "date": "2024-05-10", "map_name": "Tutorial", "map": null, "seconds_played": 600}
+/// ]"#).unwrap();
+///
+/// let playtime = player.playtime();
+/// assert_eq!(playtime.weekday_seconds()[1], 3600); // Tuesday
+/// assert_eq!(playtime.longest_active_streak().unwrap().days(), 2);
+/// assert_eq!(playtime.active_streaks().len(), 2);
+/// assert_eq!(playtime.favourite_time_of_day(), None);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Playtime<'a> {
+    player: &'a Player,
+}
+
+impl<'a> Playtime<'a> {
+    pub fn new(player: &'a Player) -> Self {
+        Self { player }
+    }
+
+    /// Seconds played per day.
+    pub fn daily_seconds(&self) -> BTreeMap<NaiveDate, u64> {
+        let mut days: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+        for activity in &self.player.recent_activity {
+            if let Some(date) = activity.date.value() {
+                *days.entry(date).or_default() += activity.seconds_played.max(0) as u64;
+            }
+        }
+        days
+    }
+
+    /// Seconds played per weekday, Monday first.
+    pub fn weekday_seconds(&self) -> [u64; 7] {
+        let mut weekdays = [0; 7];
+        for (date, seconds) in self.daily_seconds() {
+            weekdays[date.weekday().num_days_from_monday() as usize] += seconds;
+        }
+        weekdays
+    }
+
+    /// Calendar heatmap: seconds played per day, keyed by ISO week start (Monday), Monday first.
+    pub fn calendar_heatmap(&self) -> BTreeMap<NaiveDate, [u64; 7]> {
+        let mut weeks: BTreeMap<NaiveDate, [u64; 7]> = BTreeMap::new();
+        for (date, seconds) in self.daily_seconds() {
+            let weekday = date.weekday().num_days_from_monday();
+            let monday = date - Duration::days(weekday as i64);
+            weeks.entry(monday).or_default()[weekday as usize] += seconds;
+        }
+        weeks
+    }
+
+    /// Finish timestamps from `finishes` and `recent_finishes`, deduplicated and sorted,
+    /// paired with the race time.
+    fn finish_times(&self) -> Vec<(NaiveDateTime, RaceTime)> {
+        let mut seen: BTreeSet<(NaiveDateTime, &str)> = BTreeSet::new();
+        let finishes = self
+            .player
+            .finishes
+            .iter()
+            .map(|f| (&f.timestamp, f.map.map.as_str(), f.race_time()));
+        let recent = self
+            .player
+            .recent_finishes
+            .iter()
+            .map(|f| (&f.timestamp, f.map.map.as_str(), f.race_time()));
+
+        let mut times: Vec<(NaiveDateTime, RaceTime)> = finishes
+            .chain(recent)
+            .filter_map(|(timestamp, map, time)| {
+                let timestamp = timestamp.value()?;
+                seen.insert((timestamp, map)).then_some((timestamp, time))
+            })
+            .collect();
+        times.sort_by_key(|&(timestamp, _)| timestamp);
+        times
+    }
+
+    /// Finishes per weekday (Monday first) and hour.
+    pub fn hourly_heatmap(&self) -> [[u32; 24]; 7] {
+        let mut heatmap = [[0; 24]; 7];
+        for (timestamp, _) in self.finish_times() {
+            let weekday = timestamp.weekday().num_days_from_monday() as usize;
+            heatmap[weekday][timestamp.hour() as usize] += 1;
+        }
+        heatmap
+    }
+
+    /// The part of the day with the most finishes, `None` without finishes.
+    pub fn favourite_time_of_day(&self) -> Option<TimeOfDay> {
+        let mut counts: HashMap<TimeOfDay, u32> = HashMap::new();
+        for row in self.hourly_heatmap() {
+            for (hour, count) in row.into_iter().enumerate() {
+                *counts.entry(TimeOfDay::from_hour(hour as u32)).or_default() += count;
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|&(_, count)| count > 0)
+            .max_by_key(|&(time, count)| (count, std::cmp::Reverse(time)))
+            .map(|(time, _)| time)
+    }
+
+    /// Groups finishes into sessions, starting a new one after `max_gap` without a run.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use chrono::Duration;
+    /// use ddapi_rs::prelude::ddstats::{Player, RecentFinish, StatsMap};
+    ///
+    /// let finish = |map: &str, time: f64, timestamp: &str| RecentFinish {
+    ///     map: StatsMap { map: map.to_string(), ..Default::default() },
+    ///     time,
+    ///     timestamp: timestamp.into(),
+    ///     ..Default::default()
+    /// };
+    /// let mut player = Player::default();
+    /// player.recent_finishes = vec![
+    ///     finish("a", 600.0, "2024-05-06 20:00:00"),
+    ///     finish("b", 300.0, "2024-05-06 20:30:00"),
+    ///     finish("c", 60.0, "2024-05-07 09:00:00"),
+    /// ];
+    ///
+    /// let sessions = player.playtime().sessions(Duration::hours(1));
+    /// assert_eq!(sessions.len(), 2);
+    /// assert_eq!((sessions[0].finishes, sessions[0].duration().num_minutes()), (2, 40));
+    /// ```
+    pub fn sessions(&self, max_gap: Duration) -> Vec<Session> {
+        let mut sessions: Vec<Session> = Vec::new();
+        for (timestamp, time) in self.finish_times() {
+            // Race times outside chrono's range leave the start unknown; use the finish.
+            let start = Duration::try_milliseconds(time.max(RaceTime::ZERO).as_millis())
+                .and_then(|time| timestamp.checked_sub_signed(time))
+                .unwrap_or(timestamp);
+            match sessions.last_mut() {
+                Some(session) if start - session.end <= max_gap => {
+                    session.start = session.start.min(start);
+                    session.end = timestamp;
+                    session.finishes += 1;
+                }
+                _ => sessions.push(Session {
+                    start,
+                    end: timestamp,
+                    finishes: 1,
+                }),
+            }
+        }
+        sessions
+    }
+
+    /// Seconds played per map category (the map's server type), from `most_played_maps`.
+    ///
+    /// Maps without a known category are counted under `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::Category;
+    /// use ddapi_rs::prelude::ddstats::{MostPlayedMap, Player, StatsMap};
+    ///
+    /// let played = |server: &str, seconds_played| MostPlayedMap {
+    ///     seconds_played,
+    ///     map: Some(StatsMap { server: server.to_string(), ..Default::default() }),
+    ///     ..Default::default()
+    /// };
+    /// let mut player = Player::default();
+    /// player.most_played_maps = vec![played("Novice", 3000), played("Novice", 1000), played("Event", 1000)];
+    ///
+    /// let categories = player.playtime().per_category();
+    /// assert_eq!((categories[0].key, categories[0].share), (Some(Category::Novice), 0.8));
+    /// assert_eq!(categories[1].key, None);
+    /// ```
+    pub fn per_category(&self) -> Vec<PlaytimeShare<Option<Category>>> {
+        let mut categories: HashMap<Option<Category>, u64> = HashMap::new();
+        for played in &self.player.most_played_maps {
+            let category = played.map.as_ref().and_then(|map| map.server.parse().ok());
+            *categories.entry(category).or_default() += played.seconds_played;
+        }
+        shares(categories)
+    }
+
+    /// Seconds played per server location, from `most_played_locations`.
+    pub fn per_location(&self) -> Vec<PlaytimeShare> {
+        shares_of(&self.player.most_played_locations)
+    }
+
+    /// Seconds played per gametype, from `most_played_gametypes`.
+    pub fn per_gametype(&self) -> Vec<PlaytimeShare> {
+        shares_of(&self.player.most_played_gametypes)
+    }
+
+    /// Seconds played per month, oldest first.
+    pub fn per_month(&self) -> BTreeMap<NaiveDate, u64> {
+        let mut months: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+        for month in &self.player.playtime_per_month {
+            if let Some(date) = month.year_month.value() {
+                *months.entry(date).or_default() += month.seconds_played;
+            }
+        }
+        months
+    }
+
+    /// All runs of consecutive active days, oldest first.
+    pub fn active_streaks(&self) -> Vec<ActiveStreak> {
+        let mut streaks: Vec<ActiveStreak> = Vec::new();
+        let days = self
+            .daily_seconds()
+            .into_iter()
+            .filter(|&(_, seconds)| seconds > 0);
+        for (date, _) in days {
+            match streaks.last_mut() {
+                Some(streak) if streak.end.succ_opt() == Some(date) => streak.end = date,
+                _ => streaks.push(ActiveStreak {
+                    start: date,
+                    end: date,
+                }),
+            }
+        }
+        streaks
+    }
+
+    /// The longest streak; ties go to the most recent one.
+    pub fn longest_active_streak(&self) -> Option<ActiveStreak> {
+        self.active_streaks()
+            .into_iter()
+            .max_by_key(|s| (s.days(), s.start))
+    }
+
+    /// The streak still running on `today` (or that ended yesterday).
+    pub fn current_active_streak(&self, today: NaiveDate) -> Option<ActiveStreak> {
+        self.active_streaks()
+            .pop()
+            .filter(|s| s.end == today || s.end.succ_opt() == Some(today))
+    }
+}
+
+fn shares_of(played: &[MostPlayed]) -> Vec<PlaytimeShare> {
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for entry in played {
+        *totals.entry(entry.key.clone()).or_default() += entry.seconds_played;
+    }
+    shares(totals)
+}
+
+fn shares<K: Ord + Hash>(totals: HashMap<K, u64>) -> Vec<PlaytimeShare<K>> {
+    let sum: u64 = totals.values().sum();
+    let mut shares: Vec<PlaytimeShare<K>> = totals
+        .into_iter()
+        .map(|(key, seconds_played)| PlaytimeShare {
+            key,
+            seconds_played,
+            share: if sum == 0 {
+                0.0
+            } else {
+                seconds_played as f64 / sum as f64
+            },
+        })
+        .collect();
+    shares.sort_by(|a, b| {
+        b.seconds_played
+            .cmp(&a.seconds_played)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    shares
+}
+
+impl Player {
+    pub fn playtime(&self) -> Playtime<'_> {
+        Playtime::new(self)
+    }
+}
@@ -0,0 +1,238 @@
+use super::{Player, PointsGraph};
+use chrono::{Datelike, Days, NaiveDate};
+use std::fmt::Write;
+
+/// Cumulative points on one day.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointsSample {
+    pub date: NaiveDate,
+    pub points: i64,
+    pub rank_points: i64,
+    pub team_points: i64,
+}
+
+impl PointsSample {
+    /// Share of rank points in `rank_points + team_points`, `0.0` when both are zero.
+    pub fn rank_share(&self) -> f64 {
+        let total = self.rank_points + self.team_points;
+        if total == 0 {
+            0.0
+        } else {
+            self.rank_points as f64 / total as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Period {
+    /// ISO weeks, starting on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// First day of the period containing `date`.
+    pub fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Week => date - Days::new(date.weekday().num_days_from_monday() as u64),
+            Period::Month => date.with_day(1).unwrap_or(date),
+            Period::Year => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Points gained during one period.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeriodGain {
+    /// First day of the period.
+    pub start: NaiveDate,
+    pub points: i64,
+    pub rank_points: i64,
+    pub team_points: i64,
+}
+
+/// Consecutive days on which points were gained.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub points: i64,
+}
+
+impl Streak {
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+}
+
+/// A player's points over time, built from `ddstats::Player::points_graph`.
+///
+/// Samples are sorted by date, one per day (the last one wins) and entries
+/// whose date could not be parsed are dropped. Points before the first
+/// sample are taken to be zero.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::{Period, PointsGraph, PointsHistory};
+///
+/// let graph: Vec<PointsGraph> = serde_json::from_str(r#"[
+///     {"date": "2024-01-30", "points": 10, "rank_points": 0, "team_points": 0},
+///     {"date": "2024-01-31", "points": 25, "rank_points": 2, "team_points": 0},
+///     {"date": "2024-02-03", "points": 40, "rank_points": 2, "team_points": 5}
+/// ]"#).unwrap();
+/// let history = PointsHistory::new(&graph);
+///
+/// let daily = history.daily();
+/// assert_eq!(daily.samples().len(), 5);
+/// assert_eq!(daily.samples()[3].points, 25);
+///
+/// let months: Vec<i64> = history.gains(Period::Month).iter().map(|g| g.points).collect();
+/// assert_eq!(months, vec![25, 15]);
+///
+/// let streak = history.best_streak().unwrap();
+/// assert_eq!((streak.days(), streak.points), (2, 25));
+///
+/// assert!(history.to_csv().starts_with("date,points,rank_points,team_points\n2024-01-30,10,0,0\n"));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct PointsHistory {
+    samples: Vec<PointsSample>,
+}
+
+impl PointsHistory {
+    pub fn new(graph: &[PointsGraph]) -> Self {
+        let mut samples: Vec<PointsSample> = graph
+            .iter()
+            .filter_map(|entry| {
+                Some(PointsSample {
+                    date: entry.date.value()?,
+                    points: entry.points,
+                    rank_points: entry.rank_points,
+                    team_points: entry.team_points,
+                })
+            })
+            .collect();
+        samples.sort_by_key(|s| s.date);
+        // Keep the last sample of each day.
+        samples.reverse();
+        samples.dedup_by_key(|s| s.date);
+        samples.reverse();
+        Self { samples }
+    }
+
+    pub fn samples(&self) -> &[PointsSample] {
+        &self.samples
+    }
+
+    pub fn first(&self) -> Option<&PointsSample> {
+        self.samples.first()
+    }
+
+    pub fn last(&self) -> Option<&PointsSample> {
+        self.samples.last()
+    }
+
+    /// Cumulative points at the end of `date`.
+    pub fn at(&self, date: NaiveDate) -> PointsSample {
+        let index = self.samples.partition_point(|s| s.date <= date);
+        match index.checked_sub(1) {
+            Some(i) => PointsSample {
+                date,
+                ..self.samples[i]
+            },
+            None => PointsSample {
+                date,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// One sample per day from the first to the last, repeating the previous value on gaps.
+    pub fn daily(&self) -> PointsHistory {
+        let (Some(first), Some(last)) = (self.first(), self.last()) else {
+            return PointsHistory::default();
+        };
+        let samples = first
+            .date
+            .iter_days()
+            .take_while(|date| *date <= last.date)
+            .map(|date| self.at(date))
+            .collect();
+        PointsHistory { samples }
+    }
+
+    /// Points gained per period, including periods without gains.
+    pub fn gains(&self, period: Period) -> Vec<PeriodGain> {
+        let mut gains: Vec<PeriodGain> = Vec::new();
+        let mut previous = PointsSample::default();
+        for day in self.daily().samples {
+            let start = period.start(day.date);
+            if gains.last().is_none_or(|g| g.start != start) {
+                gains.push(PeriodGain {
+                    start,
+                    ..Default::default()
+                });
+            }
+            if let Some(gain) = gains.last_mut() {
+                gain.points += day.points - previous.points;
+                gain.rank_points += day.rank_points - previous.rank_points;
+                gain.team_points += day.team_points - previous.team_points;
+            }
+            previous = day;
+        }
+        gains
+    }
+
+    /// All runs of consecutive days with a points gain, oldest first.
+    pub fn streaks(&self) -> Vec<Streak> {
+        let mut streaks: Vec<Streak> = Vec::new();
+        let mut previous = PointsSample::default();
+        let mut in_streak = false;
+        for day in self.daily().samples {
+            let gained = day.points - previous.points;
+            if gained > 0 {
+                match streaks.last_mut() {
+                    Some(streak) if in_streak => {
+                        streak.end = day.date;
+                        streak.points += gained;
+                    }
+                    _ => streaks.push(Streak {
+                        start: day.date,
+                        end: day.date,
+                        points: gained,
+                    }),
+                }
+            }
+            in_streak = gained > 0;
+            previous = day;
+        }
+        streaks
+    }
+
+    /// The longest streak; ties go to the one with more points, then the most recent.
+    pub fn best_streak(&self) -> Option<Streak> {
+        self.streaks()
+            .into_iter()
+            .max_by_key(|s| (s.days(), s.points, s.start))
+    }
+
+    /// `date,points,rank_points,team_points` rows with a header line.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("date,points,rank_points,team_points\n");
+        for s in &self.samples {
+            let _ = writeln!(
+                out,
+                "{},{},{},{}",
+                s.date, s.points, s.rank_points, s.team_points
+            );
+        }
+        out
+    }
+}
+
+impl Player {
+    pub fn points_history(&self) -> PointsHistory {
+        PointsHistory::new(&self.points_graph)
+    }
+}
@@ -0,0 +1,63 @@
+use super::StatsMap;
+use crate::scheme::DDSTATS_BASE_URL;
+use crate::util::prelude::encode;
+use serde::{Deserialize, Serialize};
+
+/// A player search result.
+///
+/// The schema is modelled on hand-written payloads like the one below and
+/// has not been checked against a recorded ddstats.tw response yet.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::SearchPlayer;
+///
+/// let json = r#"[
+///     {"name": "ByFox", "points": 10432, "clan": "FoxNet", "country": 643},
+///     {"name": "ByFox2", "points": 12}
+/// ]"#;
+/// let results: Vec<SearchPlayer> = serde_json::from_str(json).unwrap();
+/// assert_eq!(results[0].clan.as_deref(), Some("FoxNet"));
+/// assert_eq!(results[1].country, None);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchPlayer {
+    pub name: String,
+    #[serde(default)]
+    pub points: i64,
+    pub clan: Option<String>,
+    pub country: Option<i32>,
+}
+
+impl SearchPlayer {
+    pub fn api(query: &str) -> String {
+        format!(
+            "https://{}/search/json?q={}",
+            DDSTATS_BASE_URL,
+            encode(query)
+        )
+    }
+}
+
+impl StatsMap {
+    /// Map search, assumed to answer with the same objects as [`StatsMap::api`].
+    ///
+    /// Not checked against a recorded ddstats.tw response yet.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::ddstats::StatsMap;
+    ///
+    /// let json = r#"[{"map": "Fox", "server": "Novice", "points": 2, "stars": 2, "mapper": "ByFox", "timestamp": null}]"#;
+    /// let maps: Vec<StatsMap> = serde_json::from_str(json).unwrap();
+    /// assert_eq!(maps[0].mapper, "ByFox");
+    /// assert_eq!(StatsMap::search_api("Fox"), "https://ddstats.tw/search/maps/json?q=Fox");
+    /// ```
+    pub fn search_api(query: &str) -> String {
+        format!(
+            "https://{}/search/maps/json?q={}",
+            DDSTATS_BASE_URL,
+            encode(query)
+        )
+    }
+}
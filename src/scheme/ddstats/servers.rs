@@ -0,0 +1,72 @@
+use crate::scheme::DDSTATS_BASE_URL;
+use crate::util::prelude::encode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsServerPlayer {
+    pub name: String,
+    pub clan: Option<String>,
+    pub country: Option<i32>,
+    #[serde(default)]
+    pub is_player: bool,
+}
+
+/// A server as listed on ddstats.tw.
+///
+/// Modelled on a hand-written payload like the one below; the fields have
+/// not been checked against a recorded ddstats.tw response yet.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::StatsServer;
+///
+/// let json = r#"[{
+///     "address": "tw-0.7+udp://45.141.57.22:8303",
+///     "name": "DDNet GER10 - Novice",
+///     "map": "Fox",
+///     "game_type": "DDraceNetwork",
+///     "location": "eu:de",
+///     "community": "ddnet",
+///     "num_clients": 1,
+///     "max_clients": 64,
+///     "players": [{"name": "ByFox", "clan": "", "country": 643, "is_player": true}]
+/// }]"#;
+/// let servers: Vec<StatsServer> = serde_json::from_str(json).unwrap();
+/// assert_eq!(servers[0].players[0].name, "ByFox");
+/// assert_eq!(servers[0].community.as_deref(), Some("ddnet"));
+/// assert_eq!(StatsServer::api_community("kog"), "https://ddstats.tw/servers/json?community=kog");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsServer {
+    pub address: String,
+    pub name: String,
+    pub map: String,
+    pub game_type: Option<String>,
+    pub location: Option<String>,
+    pub community: Option<String>,
+    #[serde(default)]
+    pub num_clients: u32,
+    #[serde(default)]
+    pub max_clients: u32,
+    #[serde(default)]
+    pub players: Vec<StatsServerPlayer>,
+}
+
+impl StatsServer {
+    pub fn api() -> String {
+        format!("https://{}/servers/json", DDSTATS_BASE_URL)
+    }
+
+    /// Servers of one community, e.g. `"ddnet"` or `"kog"`.
+    pub fn api_community(community: &str) -> String {
+        format!(
+            "https://{}/servers/json?community={}",
+            DDSTATS_BASE_URL,
+            encode(community)
+        )
+    }
+
+    pub fn url() -> String {
+        format!("https://{}/servers", DDSTATS_BASE_URL)
+    }
+}
@@ -0,0 +1,339 @@
+use super::{RecentFinish, TimeCpsSMap};
+use crate::util::prelude::RaceTime;
+use serde::de::{IgnoredAny, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+
+pub const MAX_CHECKPOINTS: usize = 25;
+
+/// The `cp1`..`cp25` checkpoint times of a finish, in seconds.
+///
+/// Maps with fewer checkpoints leave the remaining ones at zero;
+/// [`Checkpoints::as_slice`] only returns the real splits.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::TimeCpsSMap;
+///
+/// let row: TimeCpsSMap =
+///     serde_json::from_str(r#"{"name": "nameless tee", "cp1": 4.5, "cp2": 9.25, "cp3": 0.0, "time": 12.0}"#)
+///         .unwrap();
+/// assert_eq!(row.splits(), &[4.5, 9.25]);
+///
+/// let segments: Vec<String> = row.segments().iter().map(|s| s.to_string()).collect();
+/// assert_eq!(segments, ["00:04.50", "00:04.75", "00:02.75"]);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Checkpoints(pub [f64; MAX_CHECKPOINTS]);
+
+impl Checkpoints {
+    /// The checkpoint number of a `cp1`..`cp25` key.
+    fn number(key: &str) -> Option<usize> {
+        key.strip_prefix("cp")
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|n| (1..=MAX_CHECKPOINTS).contains(n))
+    }
+
+    /// Whether `key` is one of the `cp1`..`cp25` keys.
+    pub(crate) fn is_key(key: &str) -> bool {
+        Self::number(key).is_some()
+    }
+
+    /// The checkpoints up to the last non-zero one.
+    pub fn as_slice(&self) -> &[f64] {
+        let len = self.0.iter().rposition(|&cp| cp > 0.0).map_or(0, |i| i + 1);
+        &self.0[..len]
+    }
+
+    pub fn race_times(&self) -> Vec<RaceTime> {
+        self.as_slice()
+            .iter()
+            .map(|&cp| RaceTime::from_secs_f64(cp))
+            .collect()
+    }
+
+    /// Time spent between consecutive checkpoints, ending with the segment to `finish`.
+    pub fn segments(&self, finish: RaceTime) -> Vec<RaceTime> {
+        let mut last = RaceTime::ZERO;
+        self.race_times()
+            .into_iter()
+            .chain(std::iter::once(finish))
+            .map(|cp| {
+                let segment = cp - last;
+                last = cp;
+                segment
+            })
+            .collect()
+    }
+}
+
+impl Serialize for Checkpoints {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(MAX_CHECKPOINTS))?;
+        for (i, cp) in self.0.iter().enumerate() {
+            map.serialize_entry(&format!("cp{}", i + 1), cp)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Checkpoints {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CheckpointsVisitor;
+
+        impl<'de> Visitor<'de> for CheckpointsVisitor {
+            type Value = Checkpoints;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map with cp1..cp25 fields")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut checkpoints = Checkpoints::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    match Checkpoints::number(&key) {
+                        Some(n) => {
+                            checkpoints.0[n - 1] = map.next_value::<Option<f64>>()?.unwrap_or(0.0)
+                        }
+                        None => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(checkpoints)
+            }
+        }
+
+        deserializer.deserialize_map(CheckpointsVisitor)
+    }
+}
+
+impl TimeCpsSMap {
+    /// The fields besides the flattened checkpoints, for drift reports.
+    pub(crate) const FIELDS: &'static [&'static str] = &["name", "time"];
+
+    pub fn splits(&self) -> &[f64] {
+        self.checkpoints.as_slice()
+    }
+
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+
+    /// Segment times: start to first checkpoint, between checkpoints, last checkpoint to finish.
+    pub fn segments(&self) -> Vec<RaceTime> {
+        self.checkpoints.segments(self.race_time())
+    }
+
+    /// Compares this player's splits with `other`'s over the checkpoints both have, plus the finish.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::ddstats::{Checkpoints, TimeCpsSMap};
+    ///
+    /// let row = |name: &str, cps: [f64; 2], time: f64| {
+    ///     let mut checkpoints = Checkpoints::default();
+    ///     checkpoints.0[..2].copy_from_slice(&cps);
+    ///     TimeCpsSMap { name: name.to_string(), checkpoints, time }
+    /// };
+    /// let a = row("a", [5.0, 10.0], 15.0);
+    /// let b = row("b", [4.0, 10.5], 14.0);
+    ///
+    /// let splits = a.compare(&b);
+    /// assert_eq!(splits.len(), 3);
+    /// assert_eq!(splits[0].delta().as_millis(), 1000);
+    /// assert_eq!(splits[1].segment_delta().as_millis(), -1500);
+    /// assert_eq!(splits[2].checkpoint, None);
+    /// ```
+    pub fn compare(&self, other: &TimeCpsSMap) -> Vec<SplitDelta> {
+        compare_splits(
+            &self.checkpoints,
+            self.race_time(),
+            &other.checkpoints,
+            other.race_time(),
+        )
+    }
+}
+
+impl RecentFinish {
+    /// The fields besides the flattened checkpoints, for drift reports.
+    pub(crate) const FIELDS: &'static [&'static str] =
+        &["map", "name", "time", "timestamp", "server"];
+
+    pub fn splits(&self) -> &[f64] {
+        self.checkpoints.as_slice()
+    }
+
+    /// Segment times: start to first checkpoint, between checkpoints, last checkpoint to finish.
+    pub fn segments(&self) -> Vec<RaceTime> {
+        self.checkpoints.segments(self.race_time())
+    }
+
+    pub fn race_time(&self) -> RaceTime {
+        RaceTime::from_secs_f64(self.time)
+    }
+}
+
+/// One checkpoint (or the finish) of a two-player split comparison.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitDelta {
+    /// 1-based checkpoint number, `None` for the finish.
+    pub checkpoint: Option<usize>,
+    pub time_a: RaceTime,
+    pub time_b: RaceTime,
+    /// Time spent since the previous checkpoint.
+    pub segment_a: RaceTime,
+    pub segment_b: RaceTime,
+}
+
+impl SplitDelta {
+    /// Cumulative difference; negative when player A is ahead.
+    pub fn delta(&self) -> RaceTime {
+        self.time_a - self.time_b
+    }
+
+    /// Difference on this segment alone; negative when player A gained time.
+    pub fn segment_delta(&self) -> RaceTime {
+        self.segment_a - self.segment_b
+    }
+}
+
+fn compare_splits(
+    a: &Checkpoints,
+    finish_a: RaceTime,
+    b: &Checkpoints,
+    finish_b: RaceTime,
+) -> Vec<SplitDelta> {
+    let (times_a, times_b) = (a.race_times(), b.race_times());
+    let common = times_a.len().min(times_b.len());
+    let points = times_a[..common]
+        .iter()
+        .zip(&times_b[..common])
+        .enumerate()
+        .map(|(i, (&a, &b))| (Some(i + 1), a, b))
+        .chain(std::iter::once((None, finish_a, finish_b)));
+
+    let (mut last_a, mut last_b) = (RaceTime::ZERO, RaceTime::ZERO);
+    points
+        .map(|(checkpoint, time_a, time_b)| {
+            let split = SplitDelta {
+                checkpoint,
+                time_a,
+                time_b,
+                segment_a: time_a - last_a,
+                segment_b: time_b - last_b,
+            };
+            (last_a, last_b) = (time_a, time_b);
+            split
+        })
+        .collect()
+}
+
+/// The fastest time any player set on one segment.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct BestSegment {
+    pub time: RaceTime,
+    pub player: String,
+}
+
+/// Theoretical best run built from the fastest segments of all `time_cps` rows.
+///
+/// Only rows with the most common number of splits are used, so players
+/// that skipped checkpoints do not produce impossible segments.
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::ddstats::{Checkpoints, SumOfBest, TimeCpsSMap};
+///
+/// let row = |name: &str, cps: [f64; 2], time: f64| {
+///     let mut checkpoints = Checkpoints::default();
+///     checkpoints.0[..2].copy_from_slice(&cps);
+///     TimeCpsSMap { name: name.to_string(), checkpoints, time }
+/// };
+/// let rows = vec![row("a", [5.0, 10.0], 15.0), row("b", [4.0, 10.5], 14.0)];
+///
+/// let best = SumOfBest::new(&rows).unwrap();
+/// assert_eq!(best.total().to_string(), "00:12.50");
+/// assert_eq!(best.segments[0].player, "b");
+///
+/// let losses = best.losses(&rows[1]).unwrap();
+/// assert_eq!(losses.iter().map(|l| l.as_millis()).collect::<Vec<_>>(), vec![0, 1500, 0]);
+/// assert_eq!(best.worst_segment(&rows[0]), Some((2, "00:01.50".parse().unwrap())));
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SumOfBest {
+    pub segments: Vec<BestSegment>,
+}
+
+impl SumOfBest {
+    pub fn new(rows: &[TimeCpsSMap]) -> Option<Self> {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for row in rows {
+            *counts.entry(row.splits().len()).or_default() += 1;
+        }
+        let splits = counts
+            .into_iter()
+            .max_by_key(|&(splits, count)| (count, splits))
+            .map(|(splits, _)| splits)?;
+
+        let mut best: Vec<BestSegment> = Vec::new();
+        for row in rows.iter().filter(|row| row.splits().len() == splits) {
+            for (i, segment) in row.segments().into_iter().enumerate() {
+                match best.get_mut(i) {
+                    Some(current) if current.time <= segment => {}
+                    Some(current) => {
+                        *current = BestSegment {
+                            time: segment,
+                            player: row.name.clone(),
+                        }
+                    }
+                    None => best.push(BestSegment {
+                        time: segment,
+                        player: row.name.clone(),
+                    }),
+                }
+            }
+        }
+        Some(Self { segments: best })
+    }
+
+    pub fn total(&self) -> RaceTime {
+        self.segments.iter().map(|s| s.time).sum()
+    }
+
+    /// Time `row` loses against the best segment, per segment.
+    ///
+    /// `None` if the row has a different number of splits.
+    pub fn losses(&self, row: &TimeCpsSMap) -> Option<Vec<RaceTime>> {
+        let segments = row.segments();
+        if segments.len() != self.segments.len() {
+            return None;
+        }
+        Some(
+            segments
+                .iter()
+                .zip(&self.segments)
+                .map(|(own, best)| *own - best.time)
+                .collect(),
+        )
+    }
+
+    /// Index and size of the segment where `row` loses the most time.
+    pub fn worst_segment(&self, row: &TimeCpsSMap) -> Option<(usize, RaceTime)> {
+        self.losses(row)?
+            .into_iter()
+            .enumerate()
+            .max_by_key(|&(_, loss)| loss)
+    }
+}
@@ -9,14 +9,19 @@ const DDNET_BASE_URL: &str = "ddnet.org";
 #[cfg(feature = "ddstats")]
 const DDSTATS_BASE_URL: &str = "ddstats.tw";
 
-fn serialize_datetime_timestamp<S>(date: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
+pub(crate) fn serialize_datetime_timestamp<S>(
+    date: &NaiveDateTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
     serializer.serialize_i64(date.and_utc().timestamp())
 }
 
-fn deserialize_datetime_timestamp<'de, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
+pub(crate) fn deserialize_datetime_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<NaiveDateTime, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -42,6 +47,15 @@ where
             Ok(datetime.naive_utc())
         }
 
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let timestamp =
+                i64::try_from(value).map_err(|_| E::custom("timestamp out of range"))?;
+            self.visit_i64(timestamp)
+        }
+
         fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
         where
             E: de::Error,
@@ -60,6 +74,10 @@ where
     deserializer.deserialize_any(NaiveDateTimeVisitor)
 }
 
+mod category;
+
+pub use category::{Category, ParseCategoryError};
+
 #[cfg(feature = "ddnet")]
 pub mod ddnet;
 
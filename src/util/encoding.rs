@@ -159,3 +159,21 @@ fn hex_upper(n: u8) -> char {
         _ => unreachable!(),
     }
 }
+
+/// Whether `value` is a sha256 digest in hex: exactly 64 ASCII hex digits, in either case.
+///
+/// Hashes reported by servers end up in URLs and file names, so they are
+/// checked with this before use.
+///
+/// # Examples
+///
+/// ```
+/// use ddapi_rs::prelude::is_sha256;
+///
+/// assert!(is_sha256(&"AB".repeat(32)));
+/// assert!(!is_sha256("../../etc/passwd"));
+/// assert!(!is_sha256(&"ab".repeat(31)));
+/// ```
+pub fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
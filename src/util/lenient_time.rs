@@ -0,0 +1,272 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Number;
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+
+const DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%d.%m.%Y %H:%M:%S",
+];
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"];
+
+/// Types that [`LenientTime`] can parse from the formats the APIs use.
+pub trait LenientParse: Sized {
+    fn parse_lenient(value: &str) -> Option<Self>;
+}
+
+impl LenientParse for NaiveDateTime {
+    /// Accepts RFC 3339 (converted to UTC), ISO 8601 with `T` or a space,
+    /// plain dates (midnight), year-months (first day) and unix timestamps,
+    /// with fractional seconds rounded to milliseconds.
+    fn parse_lenient(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Some(datetime.naive_utc());
+        }
+        if let Some(datetime) = DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        {
+            return Some(datetime);
+        }
+        if let Some(datetime) = parse_timestamp(value) {
+            return Some(datetime.naive_utc());
+        }
+        NaiveDate::parse_lenient(value).map(|date| date.and_time(NaiveTime::MIN))
+    }
+}
+
+impl LenientParse for NaiveDate {
+    /// Accepts dates, year-months (first day) and anything
+    /// [`NaiveDateTime`] accepts (the time is dropped).
+    fn parse_lenient(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(date) = DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        {
+            return Some(date);
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d") {
+            return Some(date);
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Some(datetime.naive_utc().date());
+        }
+        if let Some(datetime) = DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        {
+            return Some(datetime.date());
+        }
+        parse_timestamp(value).map(|d| d.date_naive())
+    }
+}
+
+/// Parses unix timestamps in whole or fractional seconds.
+fn parse_timestamp(value: &str) -> Option<DateTime<chrono::Utc>> {
+    if let Ok(timestamp) = value.parse::<i64>() {
+        return DateTime::from_timestamp(timestamp, 0);
+    }
+    // Only plain decimals, not `inf` or exponents.
+    if !value
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b == b'.' || b == b'-')
+    {
+        return None;
+    }
+    let millis = (value.parse::<f64>().ok()? * 1000.0).round();
+    if !(i64::MIN as f64..i64::MAX as f64).contains(&millis) {
+        return None;
+    }
+    DateTime::from_timestamp_millis(millis as i64)
+}
+
+/// A date or time as sent by the API, parsed when possible.
+///
+/// The original value is always kept and is what gets serialized, strings as
+/// strings and numbers as numbers, so data round-trips unchanged even when
+/// the format is not recognised. Ordering compares the parsed values, with
+/// unparsed ones sorted last by their raw text.
+///
+/// # Examples
+/// ```rust
+/// use chrono::{Datelike, NaiveDate};
+/// use ddapi_rs::prelude::{LenientDate, LenientDateTime};
+///
+/// let finish = LenientDateTime::parse("2024-05-12T15:32:11");
+/// let later = LenientDateTime::parse("2024-05-12 16:00:00");
+/// assert!(finish < later);
+/// assert_eq!((*later.get().unwrap() - *finish.get().unwrap()).num_minutes(), 27);
+///
+/// let month = LenientDate::parse("2024-05");
+/// assert_eq!(month.get(), NaiveDate::from_ymd_opt(2024, 5, 1).as_ref());
+///
+/// let odd = LenientDate::parse("sometime");
+/// assert_eq!((odd.get(), odd.raw()), (None, "sometime"));
+/// assert!(month < odd);
+///
+/// let json: LenientDate = serde_json::from_str("\"2024-05\"").unwrap();
+/// assert_eq!(json.get().map(|d| d.month()), Some(5));
+/// assert_eq!(serde_json::to_string(&json).unwrap(), "\"2024-05\"");
+///
+/// let json: LenientDateTime = serde_json::from_str("1715025600.5").unwrap();
+/// assert_eq!(json.get().unwrap().to_string(), "2024-05-06 20:00:00.500");
+/// assert_eq!(serde_json::to_string(&json).unwrap(), "1715025600.5");
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LenientTime<T> {
+    raw: String,
+    /// Set when the API sent a number rather than a string.
+    number: Option<Number>,
+    parsed: Option<T>,
+}
+
+pub type LenientDateTime = LenientTime<NaiveDateTime>;
+pub type LenientDate = LenientTime<NaiveDate>;
+
+impl<T: LenientParse> LenientTime<T> {
+    pub fn parse(raw: impl Into<String>) -> Self {
+        let raw = raw.into();
+        let parsed = T::parse_lenient(&raw);
+        Self {
+            raw,
+            number: None,
+            parsed,
+        }
+    }
+
+    fn from_number(number: Number) -> Self {
+        Self {
+            number: Some(number.clone()),
+            ..Self::parse(number.to_string())
+        }
+    }
+}
+
+impl<T> LenientTime<T> {
+    /// The parsed value, `None` if the format was not recognised.
+    pub fn get(&self) -> Option<&T> {
+        self.parsed.as_ref()
+    }
+
+    /// The value exactly as the API sent it; numbers in their JSON form.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.is_some()
+    }
+}
+
+impl<T: Copy> LenientTime<T> {
+    pub fn value(&self) -> Option<T> {
+        self.parsed
+    }
+}
+
+impl<T: LenientParse> From<String> for LenientTime<T> {
+    fn from(raw: String) -> Self {
+        Self::parse(raw)
+    }
+}
+
+impl<T: LenientParse> From<&str> for LenientTime<T> {
+    fn from(raw: &str) -> Self {
+        Self::parse(raw)
+    }
+}
+
+impl<T> fmt::Display for LenientTime<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl<T: Ord> PartialOrd for LenientTime<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for LenientTime<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.parsed, &other.parsed) {
+            (Some(a), Some(b)) => a.cmp(b).then_with(|| self.raw.cmp(&other.raw)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => self.raw.cmp(&other.raw),
+        }
+    }
+}
+
+impl<T> Serialize for LenientTime<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.number {
+            Some(number) => number.serialize(serializer),
+            None => serializer.serialize_str(&self.raw),
+        }
+    }
+}
+
+impl<'de, T: LenientParse> Deserialize<'de> for LenientTime<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LenientTimeVisitor<T>(PhantomData<T>);
+
+        impl<T: LenientParse> Visitor<'_> for LenientTimeVisitor<T> {
+            type Value = LenientTime<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a date string or a unix timestamp")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(LenientTime::parse(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(LenientTime::from_number(value.into()))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(LenientTime::from_number(value.into()))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(match Number::from_f64(value) {
+                    Some(number) => LenientTime::from_number(number),
+                    None => LenientTime::parse(value.to_string()),
+                })
+            }
+        }
+
+        deserializer.deserialize_any(LenientTimeVisitor(PhantomData))
+    }
+}
@@ -1,9 +1,13 @@
 mod encoding;
+mod lenient_time;
+mod race_time;
 mod time;
 mod tw_addr;
 
 pub mod prelude {
     pub use crate::util::encoding::*;
+    pub use crate::util::lenient_time::*;
+    pub use crate::util::race_time::*;
     pub use crate::util::time::*;
     pub use crate::util::tw_addr::*;
 }
@@ -0,0 +1,340 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A DDNet race time with exact millisecond resolution.
+///
+/// The APIs report times as `f64` seconds (`DRank::time`, `LatestFinishes::time`, ...).
+/// `RaceTime` rounds them to whole milliseconds once, so comparisons, sums and
+/// deltas no longer accumulate floating point noise. Values may be negative,
+/// which is what you get when subtracting a slower time from a faster one.
+/// The operators saturate at the ends of the range; [`RaceTime::checked_add`]
+/// and [`RaceTime::checked_sub`] report overflow instead.
+///
+/// Serializes as `f64` seconds like the APIs do; deserializes from numbers or
+/// from the formatted strings accepted by [`RaceTime::parse`].
+///
+/// # Examples
+/// ```rust
+/// use ddapi_rs::prelude::RaceTime;
+///
+/// let time = RaceTime::from_secs_f64(83.456);
+/// assert_eq!(time.format_website(), "01:23.45");
+/// assert_eq!(time.format_ingame(), "01:23.456");
+/// assert_eq!("1:23.456".parse::<RaceTime>().unwrap(), time);
+///
+/// let pb = RaceTime::from_millis(80_000);
+/// assert_eq!((pb - time).to_string(), "-00:03.45");
+/// assert!(pb < time);
+/// assert_eq!(RaceTime::from_millis(i64::MAX) + pb, RaceTime::from_millis(i64::MAX));
+/// assert_eq!(RaceTime::from_millis(i64::MAX).checked_add(pb), None);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RaceTime(i64);
+
+impl RaceTime {
+    pub const ZERO: RaceTime = RaceTime(0);
+
+    pub const fn from_millis(millis: i64) -> Self {
+        RaceTime(millis)
+    }
+
+    pub const fn from_centis(centis: i64) -> Self {
+        RaceTime(centis.saturating_mul(10))
+    }
+
+    /// Rounds `seconds` to the nearest millisecond.
+    pub fn from_secs_f64(seconds: f64) -> Self {
+        RaceTime((seconds * 1000.0).round() as i64)
+    }
+
+    pub const fn as_millis(&self) -> i64 {
+        self.0
+    }
+
+    /// Whole centiseconds, truncated toward zero.
+    pub const fn as_centis(&self) -> i64 {
+        self.0 / 10
+    }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+
+    pub const fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub const fn abs(&self) -> Self {
+        RaceTime(self.0.saturating_abs())
+    }
+
+    /// `self + rhs`, or `None` on overflow.
+    pub const fn checked_add(self, rhs: RaceTime) -> Option<RaceTime> {
+        match self.0.checked_add(rhs.0) {
+            Some(millis) => Some(RaceTime(millis)),
+            None => None,
+        }
+    }
+
+    /// `self - rhs`, or `None` on overflow.
+    pub const fn checked_sub(self, rhs: RaceTime) -> Option<RaceTime> {
+        match self.0.checked_sub(rhs.0) {
+            Some(millis) => Some(RaceTime(millis)),
+            None => None,
+        }
+    }
+
+    /// Formats like the ddnet.org rank tables: `MM:SS.cc`, or `HH:MM:SS.cc`
+    /// from one hour on. Centiseconds are truncated, never rounded up.
+    pub fn format_website(&self) -> String {
+        self.format_with(2)
+    }
+
+    /// Formats like the in-game scoreboard and finish messages: `MM:SS.mmm`,
+    /// or `HH:MM:SS.mmm` from one hour on.
+    pub fn format_ingame(&self) -> String {
+        self.format_with(3)
+    }
+
+    fn format_with(&self, fraction_digits: u32) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let millis = self.0.unsigned_abs();
+        let fraction = (millis % 1000) / 10_u64.pow(3 - fraction_digits);
+        let total_seconds = millis / 1000;
+        let (hours, minutes, seconds) = (
+            total_seconds / 3600,
+            total_seconds / 60 % 60,
+            total_seconds % 60,
+        );
+        let width = fraction_digits as usize;
+
+        if hours > 0 {
+            format!("{sign}{hours:02}:{minutes:02}:{seconds:02}.{fraction:0width$}")
+        } else {
+            format!("{sign}{minutes:02}:{seconds:02}.{fraction:0width$}")
+        }
+    }
+
+    /// Parses `[-][[HH:]MM:]SS[.fraction]` with up to three fraction digits,
+    /// which covers both [`format_website`](Self::format_website) and
+    /// [`format_ingame`](Self::format_ingame) output as well as plain seconds.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ddapi_rs::prelude::RaceTime;
+    ///
+    /// assert_eq!(RaceTime::parse("01:02:03.40").unwrap().as_millis(), 3_723_400);
+    /// assert_eq!(RaceTime::parse("12.5").unwrap().as_millis(), 12_500);
+    /// assert_eq!(
+    ///     RaceTime::parse("1:75.00").unwrap_err().to_string(),
+    ///     "Invalid race time: 1:75.00"
+    /// );
+    /// assert!(RaceTime::parse("99999999999999999").is_err());
+    /// assert!(serde_json::from_str::<RaceTime>("9223372036854775807").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Result<Self, ParseRaceTimeError> {
+        let invalid = || ParseRaceTimeError::Invalid(value.to_string());
+        let out_of_range = || ParseRaceTimeError::OutOfRange(value.to_string());
+
+        let trimmed = value.trim();
+        let (negative, body) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+
+        let (clock, fraction) = match body.split_once('.') {
+            Some((clock, fraction)) => (clock, fraction),
+            None => (body, ""),
+        };
+        if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let fraction_millis = if fraction.is_empty() {
+            0
+        } else {
+            let digits: i64 = fraction.parse().map_err(|_| invalid())?;
+            digits * 10_i64.pow(3 - fraction.len() as u32)
+        };
+
+        let parts: Vec<&str> = clock.split(':').collect();
+        if parts.len() > 3 {
+            return Err(invalid());
+        }
+        let mut seconds: i64 = 0;
+        for (i, part) in parts.iter().enumerate() {
+            if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let n: i64 = part.parse().map_err(|_| invalid())?;
+            // Only the leading component may exceed its unit, e.g. "75:00.00".
+            if i > 0 && n >= 60 {
+                return Err(invalid());
+            }
+            seconds = seconds
+                .checked_mul(60)
+                .and_then(|s| s.checked_add(n))
+                .ok_or_else(out_of_range)?;
+        }
+
+        let millis = seconds
+            .checked_mul(1000)
+            .and_then(|ms| ms.checked_add(fraction_millis))
+            .ok_or_else(out_of_range)?;
+        Ok(RaceTime(if negative { -millis } else { millis }))
+    }
+}
+
+impl fmt::Display for RaceTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_website())
+    }
+}
+
+/// Returned when parsing a string that is not a [`RaceTime`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseRaceTimeError {
+    /// The string does not match `[-][[HH:]MM:]SS[.fraction]`.
+    Invalid(String),
+    /// The time does not fit in an `i64` of milliseconds.
+    OutOfRange(String),
+}
+
+impl fmt::Display for ParseRaceTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRaceTimeError::Invalid(value) => write!(f, "Invalid race time: {value}"),
+            ParseRaceTimeError::OutOfRange(value) => write!(f, "Race time out of range: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRaceTimeError {}
+
+impl FromStr for RaceTime {
+    type Err = ParseRaceTimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RaceTime::parse(s)
+    }
+}
+
+impl From<f64> for RaceTime {
+    fn from(seconds: f64) -> Self {
+        RaceTime::from_secs_f64(seconds)
+    }
+}
+
+impl From<RaceTime> for f64 {
+    fn from(time: RaceTime) -> Self {
+        time.as_secs_f64()
+    }
+}
+
+impl Add for RaceTime {
+    type Output = RaceTime;
+
+    fn add(self, rhs: RaceTime) -> RaceTime {
+        RaceTime(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for RaceTime {
+    fn add_assign(&mut self, rhs: RaceTime) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for RaceTime {
+    type Output = RaceTime;
+
+    fn sub(self, rhs: RaceTime) -> RaceTime {
+        RaceTime(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for RaceTime {
+    fn sub_assign(&mut self, rhs: RaceTime) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for RaceTime {
+    type Output = RaceTime;
+
+    fn neg(self) -> RaceTime {
+        RaceTime(self.0.saturating_neg())
+    }
+}
+
+impl Sum for RaceTime {
+    fn sum<I: Iterator<Item = RaceTime>>(iter: I) -> RaceTime {
+        iter.fold(RaceTime::ZERO, Add::add)
+    }
+}
+
+impl Serialize for RaceTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.as_secs_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for RaceTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RaceTimeVisitor;
+
+        impl Visitor<'_> for RaceTimeVisitor {
+            type Value = RaceTime;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("seconds as a number or a formatted race time string")
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(RaceTime::from_secs_f64(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value
+                    .checked_mul(1000)
+                    .map(RaceTime)
+                    .ok_or_else(|| E::custom("race time out of range"))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                i64::try_from(value)
+                    .ok()
+                    .and_then(|value| value.checked_mul(1000))
+                    .map(RaceTime)
+                    .ok_or_else(|| E::custom("race time out of range"))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                RaceTime::parse(value).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(RaceTimeVisitor)
+    }
+}